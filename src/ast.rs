@@ -1,11 +1,20 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::rc::Rc;
 
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
+use regex::Regex;
+
 use crate::env::Env;
 use crate::errors::{NumericError, SpressoError};
 use crate::{Token, TokenGiver, TokenHoarder};
 
 pub type FuncType = fn(Vec<Expr>, &mut Env) -> Result<Expr, SpressoError>;
+/// Like `FuncType`, but a closure which can capture state. Used for builtins registered by the
+/// host application.
+pub type NativeFuncType = Rc<dyn Fn(Vec<Expr>, &mut Env) -> Result<Expr, SpressoError>>;
 
 #[derive(Clone, Debug)]
 pub struct Expr {
@@ -23,6 +32,19 @@ impl Expr {
     pub fn new(kind: ExprKind) -> Self {
         Self { kind, tokens: None }
     }
+
+    /// Text to show to a user, like `Display` but without quotes around a string and without
+    /// trailing spaces. Strings nested inside lists are still quoted.
+    pub fn to_plain_string(&self) -> String {
+        match &self.kind {
+            ExprKind::Atom(Atom::String(string)) => string.clone(),
+            _ => {
+                let mut res = String::new();
+                plain_expr(self, &mut res);
+                res
+            }
+        }
+    }
 }
 
 impl From<ExprKind> for Expr {
@@ -51,7 +73,7 @@ impl TokenGiver for Expr {
     }
 }
 
-impl TokenGiver for Vec<Expr> {
+impl TokenGiver for [Expr] {
     fn get_tokens(&self) -> Option<Vec<Token>> {
         let mut tokens = Vec::new();
 
@@ -70,7 +92,32 @@ pub enum ExprKind {
     Atom(Atom),
     List(Vec<Expr>),
     Func(FuncType),
+    /// A builtin which decides itself which of its args are evaluated and when, like `if` or
+    /// `define`. It is called like `Func`, but giving it values with `apply` is an error.
+    SpecialForm(FuncType),
+    NativeFunc(NativeFuncType),
     Lambda(Lambda),
+    /// A compiled regular expression, so that it can be reused without compiling it again.
+    Regex(Regex),
+}
+
+impl ExprKind {
+    /// Name of the type of the value, to be used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ExprKind::Atom(Atom::Symbol(_)) => "symbol",
+            ExprKind::Atom(Atom::Number(_)) => "number",
+            ExprKind::Atom(Atom::Bool(_)) => "bool",
+            ExprKind::Atom(Atom::String(_)) => "string",
+            ExprKind::Atom(Atom::Unit) => "unit",
+            ExprKind::List(_) => "list",
+            ExprKind::Func(_)
+            | ExprKind::SpecialForm(_)
+            | ExprKind::NativeFunc(_)
+            | ExprKind::Lambda(_) => "function",
+            ExprKind::Regex(_) => "regex",
+        }
+    }
 }
 
 impl fmt::Debug for ExprKind {
@@ -79,7 +126,10 @@ impl fmt::Debug for ExprKind {
             Self::Atom(arg0) => f.debug_tuple("Atom").field(arg0).finish(),
             Self::List(arg0) => f.debug_tuple("List").field(arg0).finish(),
             Self::Func(_) => f.debug_tuple("Func").finish(),
+            Self::SpecialForm(_) => f.debug_tuple("SpecialForm").finish(),
+            Self::NativeFunc(_) => f.debug_tuple("NativeFunc").finish(),
             Self::Lambda(arg0) => f.debug_tuple("Lambda").field(arg0).finish(),
+            Self::Regex(arg0) => f.debug_tuple("Regex").field(arg0).finish(),
         }
     }
 }
@@ -89,8 +139,14 @@ impl PartialEq for ExprKind {
         match (self, other) {
             (ExprKind::Atom(l0), ExprKind::Atom(r0)) => l0 == r0,
             (ExprKind::List(l0), ExprKind::List(r0)) => l0 == r0,
-            (ExprKind::Func(l0), ExprKind::Func(r0)) => (*l0 as usize) == (*r0 as usize),
+            (ExprKind::Func(l0), ExprKind::Func(r0))
+            | (ExprKind::SpecialForm(l0), ExprKind::SpecialForm(r0)) => {
+                (*l0 as usize) == (*r0 as usize)
+            }
+            // closures cannot be compared, only the same closure is equal to itself
+            (ExprKind::NativeFunc(l0), ExprKind::NativeFunc(r0)) => Rc::ptr_eq(l0, r0),
             (ExprKind::Lambda(l0), ExprKind::Lambda(r0)) => l0 == r0,
+            (ExprKind::Regex(l0), ExprKind::Regex(r0)) => l0.as_str() == r0.as_str(),
             _ => false,
         }
     }
@@ -117,104 +173,357 @@ impl fmt::Display for Atom {
             Atom::Symbol(string) => write!(f, "{}", string),
             Atom::Number(num) => write!(f, "{}", num),
             Atom::Bool(boolean) => write!(f, "{}", boolean),
-            Atom::String(string) => write!(f, "\"{}\"", string),
+            Atom::String(string) => write!(f, "\"{}\"", escape_string(string)),
             Atom::Unit => write!(f, "()"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+/// Inverse of the unescaping done while parsing a string, so that a displayed string can be
+/// read back as the same string.
+fn escape_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Clone)]
 pub enum Number {
     Int(i64),
+    /// An integer which does not fit in an `Int`. Integers are promoted to this when an
+    /// operation overflows, and demoted back when the result fits.
+    BigInt(BigInt),
+    /// An exact fraction, like the result of `(/ 7 2)`. Always in its lowest terms and never
+    /// has a denominator of 1 (that would be an integer).
+    Ratio(BigRational),
     Float(f64),
 }
 
+impl Number {
+    /// Smallest representation of an integer: `Int` if it fits in an i64, `BigInt` otherwise.
+    pub fn from_bigint(num: BigInt) -> Number {
+        match num.to_i64() {
+            Some(num) => Number::Int(num),
+            None => Number::BigInt(num),
+        }
+    }
+
+    /// Smallest representation of a fraction: an integer if the denominator is 1, `Ratio`
+    /// otherwise.
+    pub fn from_ratio(num: BigRational) -> Number {
+        if num.is_integer() {
+            Number::from_bigint(num.to_integer())
+        } else {
+            Number::Ratio(num)
+        }
+    }
+
+    /// Parses the text of a number, like [`Number::parse_literal`] does.
+    pub fn parse(text: &str) -> Option<Number> {
+        Number::parse_literal(text).ok()
+    }
+
+    /// Parses the text of a number literal, explaining what is wrong with it when it is not a
+    /// valid number.
+    ///
+    /// A literal has an optional sign followed by one of:
+    /// - an integer: `42`, `1_000_000`, `0xff`, `0b1010` or `0o17`
+    /// - a ratio of integers: `7/2`
+    /// - a float, with an optional exponent: `2.5`, `.5`, `1e10` or `1.5e-3`
+    /// - `inf` or `nan`
+    ///
+    /// A `_` can be used between digits to separate them.
+    pub fn parse_literal(text: &str) -> Result<Number, String> {
+        let invalid = |reason: String| format!("Invalid number literal `{}`: {}", text, reason);
+
+        let (sign, unsigned) = match text.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", text.strip_prefix('+').unwrap_or(text)),
+        };
+
+        match unsigned {
+            "inf" if sign == "-" => return Ok(Number::Float(f64::NEG_INFINITY)),
+            "inf" => return Ok(Number::Float(f64::INFINITY)),
+            "nan" => return Ok(Number::Float(f64::NAN)),
+            "" => return Err(invalid("missing digits".to_string())),
+            _ => {}
+        }
+
+        for (prefix, radix) in [("0x", 16), ("0b", 2), ("0o", 8)] {
+            if let Some(digits) = unsigned.strip_prefix(prefix) {
+                let digits = strip_separators(digits, radix).map_err(invalid)?;
+                if digits.is_empty() {
+                    return Err(invalid(format!("missing digits after `{}`", prefix)));
+                }
+                let num = BigInt::parse_bytes(format!("{}{}", sign, digits).as_bytes(), radix);
+                return num
+                    .map(Number::from_bigint)
+                    .ok_or_else(|| invalid("not a valid integer".to_string()));
+            }
+        }
+
+        if let Some((numer, denom)) = unsigned.split_once('/') {
+            if denom.contains('/') {
+                return Err(invalid("more than one `/`".to_string()));
+            }
+            if numer.contains(['.', 'e', 'E']) || denom.contains(['.', 'e', 'E']) {
+                return Err(invalid(
+                    "both parts of a ratio must be integers".to_string(),
+                ));
+            }
+            let numer = parse_digits(sign, numer).map_err(invalid)?;
+            let denom = parse_digits("", denom).map_err(invalid)?;
+            if denom.is_zero() {
+                return Err(invalid("denominator is zero".to_string()));
+            }
+            return Ok(Number::from_ratio(BigRational::new(numer, denom)));
+        }
+
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+            None => (unsigned, None),
+        };
+        let (int_part, frac_part) = match mantissa.split_once('.') {
+            Some((_, frac_part)) if frac_part.contains('.') => {
+                return Err(invalid("more than one decimal point".to_string()))
+            }
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (mantissa, None),
+        };
+
+        if frac_part.is_none() && exponent.is_none() {
+            return parse_digits(sign, int_part)
+                .map(Number::from_bigint)
+                .map_err(invalid);
+        }
+
+        let int_part = strip_separators(int_part, 10).map_err(invalid)?;
+        let frac_part = strip_separators(frac_part.unwrap_or_default(), 10).map_err(invalid)?;
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(invalid("missing digits".to_string()));
+        }
+        let exponent = match exponent {
+            Some(exponent) => {
+                let (exp_sign, exp_digits) = match exponent.strip_prefix('-') {
+                    Some(exp_digits) => ("-", exp_digits),
+                    None => ("", exponent.strip_prefix('+').unwrap_or(exponent)),
+                };
+                let exp_digits = strip_separators(exp_digits, 10).map_err(invalid)?;
+                if exp_digits.is_empty() {
+                    return Err(invalid("missing digits in the exponent".to_string()));
+                }
+                format!("e{}{}", exp_sign, exp_digits)
+            }
+            None => String::new(),
+        };
+
+        format!("{}{}.{}{}", sign, int_part, frac_part, exponent)
+            .parse::<f64>()
+            .map(Number::Float)
+            .map_err(|err| invalid(err.to_string()))
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Int(num) => *num as f64,
+            Number::BigInt(num) => num.to_f64().unwrap_or(f64::NAN),
+            Number::Ratio(num) => num.to_f64().unwrap_or(f64::NAN),
+            Number::Float(num) => *num,
+        }
+    }
+
+    /// The integer as a `BigInt`, or `None` if it is not an integer.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        match self {
+            Number::Int(num) => Some(BigInt::from(*num)),
+            Number::BigInt(num) => Some(num.clone()),
+            Number::Ratio(_) | Number::Float(_) => None,
+        }
+    }
+
+    /// The exact value as a fraction, or `None` for a float.
+    pub fn to_ratio(&self) -> Option<BigRational> {
+        match self {
+            Number::Float(_) => None,
+            Number::Ratio(num) => Some(num.clone()),
+            num => num.to_bigint().map(BigRational::from_integer),
+        }
+    }
+
+    /// Whether the number is an integer or a ratio, as opposed to a float.
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Number::Float(_))
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Number::Int(num) => *num == 0,
+            Number::BigInt(num) => num.is_zero(),
+            Number::Ratio(num) => num.is_zero(),
+            Number::Float(num) => *num == 0.0,
+        }
+    }
+
+    /// Only meant to be called on integers.
+    fn into_bigint(self) -> BigInt {
+        match self {
+            Number::Int(num) => BigInt::from(num),
+            Number::BigInt(num) => num,
+            Number::Ratio(num) => num.to_integer(),
+            Number::Float(num) => BigInt::from_f64(num).unwrap_or_default(),
+        }
+    }
+
+    /// Only meant to be called on exact numbers.
+    fn into_ratio(self) -> BigRational {
+        match self {
+            Number::Ratio(num) => num,
+            Number::Float(num) => BigRational::from_float(num).unwrap_or_default(),
+            num => BigRational::from_integer(num.into_bigint()),
+        }
+    }
+
+    /// Applies an arithmetic operation.
+    ///
+    /// Integers are promoted to a `BigInt` when the result does not fit in an i64. A ratio on
+    /// either side makes the result a ratio and a float on either side makes it a float.
+    /// `int_op` returning `None` falls back to the exact `ratio_op`.
+    pub(crate) fn apply(
+        self,
+        rhs: Number,
+        int_op: fn(i64, i64) -> Option<i64>,
+        ratio_op: fn(BigRational, BigRational) -> BigRational,
+        float_op: fn(f64, f64) -> f64,
+    ) -> Number {
+        match (self, rhs) {
+            (Number::Int(lhs), Number::Int(rhs)) => match int_op(lhs, rhs) {
+                Some(res) => Number::Int(res),
+                None => Number::from_ratio(ratio_op(
+                    BigRational::from_integer(BigInt::from(lhs)),
+                    BigRational::from_integer(BigInt::from(rhs)),
+                )),
+            },
+            (Number::Float(lhs), rhs) => Number::Float(float_op(lhs, rhs.to_f64())),
+            (lhs, Number::Float(rhs)) => Number::Float(float_op(lhs.to_f64(), rhs)),
+            (lhs, rhs) => Number::from_ratio(ratio_op(lhs.into_ratio(), rhs.into_ratio())),
+        }
+    }
+}
+
+/// Removes the `_` separators from the digits of a number literal, checking that every other
+/// character is a digit in the given radix.
+fn strip_separators(digits: &str, radix: u32) -> Result<String, String> {
+    let chars: Vec<char> = digits.chars().collect();
+    let mut res = String::with_capacity(digits.len());
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_digit(radix) {
+            res.push(*c);
+        } else if *c == '_' {
+            let is_digit = |i: Option<usize>| {
+                i.and_then(|i| chars.get(i))
+                    .is_some_and(|c| c.is_digit(radix))
+            };
+            if !is_digit(i.checked_sub(1)) || !is_digit(Some(i + 1)) {
+                return Err("`_` is only allowed between digits".to_string());
+            }
+        } else {
+            return Err(format!("unexpected character `{}`", c));
+        }
+    }
+    Ok(res)
+}
+
+/// Parses the (non-empty) decimal digits of an integer.
+fn parse_digits(sign: &str, digits: &str) -> Result<BigInt, String> {
+    let digits = strip_separators(digits, 10)?;
+    if digits.is_empty() {
+        return Err("missing digits".to_string());
+    }
+    format!("{}{}", sign, digits)
+        .parse::<BigInt>()
+        .map_err(|err| err.to_string())
+}
+
 impl fmt::Display for Number {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Number::Int(num) => write!(f, "{}", num),
+            Number::BigInt(num) => write!(f, "{}", num),
+            Number::Ratio(num) => write!(f, "{}", num),
             Number::Float(num) => write!(f, "{}", num),
         }
     }
 }
 
+/// Numbers are compared by value, irrespective of how they are stored.
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(lhs), Number::Int(rhs)) => lhs.partial_cmp(rhs),
+            (Number::Float(lhs), Number::Float(rhs)) => lhs.partial_cmp(rhs),
+            (Number::Float(lhs), rhs) => lhs.partial_cmp(&rhs.to_f64()),
+            (lhs, Number::Float(rhs)) => lhs.to_f64().partial_cmp(rhs),
+            (lhs, rhs) => lhs.to_ratio().partial_cmp(&rhs.to_ratio()),
+        }
+    }
+}
+
 impl std::ops::Add<Number> for Number {
     type Output = Result<Number, SpressoError>;
     fn add(self, rhs: Number) -> Self::Output {
-        match rhs {
-            Number::Float(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs + num)),
-                Number::Int(lhs) => Ok(Number::Float(lhs as f64 + num)),
-            },
-            Number::Int(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs + num as f64)),
-                Number::Int(lhs) => Ok(Number::Int(lhs + num)),
-            },
-        }
+        Ok(self.apply(rhs, i64::checked_add, |x, y| x + y, |x, y| x + y))
     }
 }
 
 impl std::ops::Mul<Number> for Number {
     type Output = Result<Number, SpressoError>;
     fn mul(self, rhs: Number) -> Self::Output {
-        match rhs {
-            Number::Float(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs * num)),
-                Number::Int(lhs) => Ok(Number::Float(lhs as f64 * num)),
-            },
-            Number::Int(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs * num as f64)),
-                Number::Int(lhs) => Ok(Number::Int(lhs * num)),
-            },
-        }
+        Ok(self.apply(rhs, i64::checked_mul, |x, y| x * y, |x, y| x * y))
     }
 }
 
 impl std::ops::Sub<Number> for Number {
     type Output = Result<Number, SpressoError>;
     fn sub(self, rhs: Number) -> Self::Output {
-        match rhs {
-            Number::Float(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs - num)),
-                Number::Int(lhs) => Ok(Number::Float(lhs as f64 - num)),
-            },
-            Number::Int(num) => match self {
-                Number::Float(lhs) => Ok(Number::Float(lhs - num as f64)),
-                Number::Int(lhs) => Ok(Number::Int(lhs - num)),
-            },
-        }
+        Ok(self.apply(rhs, i64::checked_sub, |x, y| x - y, |x, y| x - y))
     }
 }
 
 impl std::ops::Div<Number> for Number {
     type Output = Result<Number, SpressoError>;
     fn div(self, rhs: Number) -> Self::Output {
-        match rhs {
-            Number::Float(num) => {
-                if num == 0.0 || num == -0.0 {
-                    return Err(NumericError {
-                        err: "Division By Zero".to_string(),
-                    }
-                    .into());
-                }
-                match self {
-                    Number::Float(lhs) => Ok(Number::Float(lhs / num)),
-                    Number::Int(lhs) => Ok(Number::Float(lhs as f64 / num)),
-                }
-            }
-            Number::Int(num) => {
-                if num == 0 {
-                    return Err(NumericError {
-                        err: "Division By Zero".to_string(),
-                    }
-                    .into());
-                }
-                match self {
-                    Number::Float(lhs) => Ok(Number::Float(lhs / num as f64)),
-                    Number::Int(lhs) => Ok(Number::Int(lhs / num)),
-                }
+        if rhs.is_zero() {
+            return Err(NumericError {
+                err: "Division By Zero".to_string(),
             }
+            .into());
         }
+        // integers which do not divide exactly give a ratio instead of truncating.
+        // `checked_rem` also fails for i64::MIN / -1, the only integer division which overflows.
+        Ok(self.apply(
+            rhs,
+            |x, y| match x.checked_rem(y)? {
+                0 => x.checked_div(y),
+                _ => None,
+            },
+            |x, y| x / y,
+            |x, y| x / y,
+        ))
     }
 }
 
@@ -224,6 +533,9 @@ pub struct Lambda {
     pub body: Vec<Expr>,
     pub scopes: Vec<Rc<usize>>,
     param_tokens: Vec<Token>,
+    /// Shared by all the clones of a lambda, to tell it apart from other lambdas with the same
+    /// code.
+    identity: Rc<()>,
 }
 
 impl PartialEq for Lambda {
@@ -239,8 +551,15 @@ impl Lambda {
             body,
             scopes,
             param_tokens: Vec::new(),
+            identity: Rc::new(()),
         }
     }
+
+    /// Whether both are the same lambda (or clones of it), as opposed to lambdas which only
+    /// have the same code.
+    pub fn is_same(&self, other: &Lambda) -> bool {
+        Rc::ptr_eq(&self.identity, &other.identity)
+    }
 }
 
 /// Note: Lambda itself should only store the tokens of its parameters
@@ -274,8 +593,11 @@ fn pretty_ast(ast: &Expr, level: usize, f: &mut fmt::Formatter<'_>) -> fmt::Resu
                 .try_for_each(|token| pretty_ast(token, level + 1, f))
         }
         ExprKind::Atom(token) => writeln!(f, "{}{}", "\t".repeat(level), token),
-        ExprKind::Func(..) => writeln!(f, "{}built-in function", "\t".repeat(level)),
+        ExprKind::Func(..) | ExprKind::SpecialForm(..) | ExprKind::NativeFunc(..) => {
+            writeln!(f, "{}built-in function", "\t".repeat(level))
+        }
         ExprKind::Lambda(lambda) => writeln!(f, "{}{}", "\t".repeat(level), lambda),
+        ExprKind::Regex(regex) => writeln!(f, "{}#/{}/", "\t".repeat(level), regex),
     }
 }
 
@@ -290,7 +612,31 @@ fn print_expr(ast: &Expr, _level: usize, f: &mut fmt::Formatter<'_>) -> fmt::Res
             hmm
         }
         ExprKind::Atom(token) => write!(f, "{} ", token),
-        ExprKind::Func(..) => write!(f, "built-in function "),
+        ExprKind::Func(..) | ExprKind::SpecialForm(..) | ExprKind::NativeFunc(..) => {
+            write!(f, "built-in function ")
+        }
         ExprKind::Lambda(lambda) => write!(f, "{} ", lambda),
+        ExprKind::Regex(regex) => write!(f, "#/{}/ ", regex),
+    }
+}
+
+fn plain_expr(ast: &Expr, res: &mut String) {
+    match &ast.kind {
+        ExprKind::List(list) => {
+            res.push('[');
+            for (i, expr) in list.iter().enumerate() {
+                if i > 0 {
+                    res.push(' ');
+                }
+                plain_expr(expr, res);
+            }
+            res.push(']');
+        }
+        ExprKind::Atom(token) => res.push_str(&token.to_string()),
+        ExprKind::Func(..) | ExprKind::SpecialForm(..) | ExprKind::NativeFunc(..) => {
+            res.push_str("built-in function")
+        }
+        ExprKind::Lambda(lambda) => res.push_str(&lambda.to_string()),
+        ExprKind::Regex(regex) => res.push_str(&format!("#/{}/", regex)),
     }
 }
@@ -0,0 +1,339 @@
+//! Conversions between Rust values and `Expr`, for applications embedding the interpreter.
+
+use std::collections::HashMap;
+
+use num_traits::ToPrimitive;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{execute_single, OBJECT_TAG},
+    TokenGiver, TokenHoarder,
+};
+
+/// Converts a Rust value into a lisp value.
+pub trait IntoExpr {
+    fn into_expr(self) -> Expr;
+}
+
+/// Converts an evaluated lisp value into a Rust value, failing if it has the wrong type.
+pub trait FromExpr: Sized {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError>;
+}
+
+fn type_error(expected: &str, expr: &Expr) -> SpressoError {
+    SpressoError::from(RuntimeError::from(format!(
+        "expected {}, got a {}: {}",
+        expected,
+        expr.kind.type_name(),
+        expr
+    )))
+    .maybe_with_tokens(expr.get_tokens())
+}
+
+impl IntoExpr for Expr {
+    fn into_expr(self) -> Expr {
+        self
+    }
+}
+
+impl FromExpr for Expr {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        Ok(expr)
+    }
+}
+
+impl IntoExpr for Number {
+    fn into_expr(self) -> Expr {
+        ExprKind::Atom(Atom::Number(self)).into()
+    }
+}
+
+impl FromExpr for Number {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::Atom(Atom::Number(num)) => Ok(num),
+            _ => Err(type_error("a number", &expr)),
+        }
+    }
+}
+
+impl IntoExpr for i64 {
+    fn into_expr(self) -> Expr {
+        Number::Int(self).into_expr()
+    }
+}
+
+impl FromExpr for i64 {
+    /// Only integers which fit in an i64. Floats are not truncated.
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match &expr.kind {
+            ExprKind::Atom(Atom::Number(Number::Int(int))) => Ok(*int),
+            ExprKind::Atom(Atom::Number(Number::BigInt(int))) => int
+                .to_i64()
+                .ok_or_else(|| type_error("an integer that fits in 64 bits", &expr)),
+            _ => Err(type_error("an integer", &expr)),
+        }
+    }
+}
+
+impl IntoExpr for f64 {
+    fn into_expr(self) -> Expr {
+        Number::Float(self).into_expr()
+    }
+}
+
+impl FromExpr for f64 {
+    /// Any number, converted into a float.
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match &expr.kind {
+            ExprKind::Atom(Atom::Number(num)) => Ok(num.to_f64()),
+            _ => Err(type_error("a number", &expr)),
+        }
+    }
+}
+
+impl IntoExpr for bool {
+    fn into_expr(self) -> Expr {
+        ExprKind::Atom(Atom::Bool(self)).into()
+    }
+}
+
+impl FromExpr for bool {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::Atom(Atom::Bool(val)) => Ok(val),
+            _ => Err(type_error("a bool", &expr)),
+        }
+    }
+}
+
+impl IntoExpr for String {
+    fn into_expr(self) -> Expr {
+        ExprKind::Atom(Atom::String(self)).into()
+    }
+}
+
+impl IntoExpr for &str {
+    fn into_expr(self) -> Expr {
+        self.to_string().into_expr()
+    }
+}
+
+impl FromExpr for String {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::Atom(Atom::String(string)) => Ok(string),
+            _ => Err(type_error("a string", &expr)),
+        }
+    }
+}
+
+impl IntoExpr for () {
+    fn into_expr(self) -> Expr {
+        ExprKind::Atom(Atom::Unit).into()
+    }
+}
+
+impl FromExpr for () {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::Atom(Atom::Unit) => Ok(()),
+            _ => Err(type_error("unit", &expr)),
+        }
+    }
+}
+
+impl<T: IntoExpr> IntoExpr for Vec<T> {
+    fn into_expr(self) -> Expr {
+        ExprKind::List(self.into_iter().map(IntoExpr::into_expr).collect()).into()
+    }
+}
+
+impl<T: FromExpr> FromExpr for Vec<T> {
+    /// `()` is read as unit, so it is accepted as an empty list too.
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::List(exprs) => exprs.into_iter().map(T::from_expr).collect(),
+            ExprKind::Atom(Atom::Unit) => Ok(Vec::new()),
+            _ => Err(type_error("a list", &expr)),
+        }
+    }
+}
+
+/// `None` is unit.
+impl<T: IntoExpr> IntoExpr for Option<T> {
+    fn into_expr(self) -> Expr {
+        match self {
+            Some(val) => val.into_expr(),
+            None => ().into_expr(),
+        }
+    }
+}
+
+impl<T: FromExpr> FromExpr for Option<T> {
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        match expr.kind {
+            ExprKind::Atom(Atom::Unit) => Ok(None),
+            _ => T::from_expr(expr).map(Some),
+        }
+    }
+}
+
+/// A map is an object like the ones `json-parse` returns, a list of `(key value)` pairs sorted
+/// by key and headed by the symbol `object`.
+impl<T: IntoExpr> IntoExpr for HashMap<String, T> {
+    fn into_expr(self) -> Expr {
+        let mut pairs: Vec<_> = self.into_iter().collect();
+        pairs.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+        let mut object = vec![ExprKind::Atom(Atom::Symbol(OBJECT_TAG.to_string())).into()];
+        object.extend(pairs.into_iter().map(IntoExpr::into_expr));
+        ExprKind::List(object).into()
+    }
+}
+
+impl<T: FromExpr> FromExpr for HashMap<String, T> {
+    /// Either an object or an untagged list of pairs. Keys can be either strings or symbols. A
+    /// later pair overrides an earlier one with the same key.
+    fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+        let mut pairs = match expr.kind {
+            ExprKind::List(exprs) => exprs,
+            ExprKind::Atom(Atom::Unit) => Vec::new(),
+            _ => return Err(type_error("a list of (key value) pairs", &expr)),
+        };
+        if let Some(ExprKind::Atom(Atom::Symbol(tag))) = pairs.first().map(|head| &head.kind) {
+            if tag == OBJECT_TAG {
+                pairs.remove(0);
+            }
+        }
+
+        pairs
+            .into_iter()
+            .map(|pair| match pair.kind {
+                ExprKind::List(mut kv) if kv.len() == 2 => {
+                    let val = T::from_expr(kv.pop().unwrap())?;
+                    let key = kv.pop().unwrap();
+                    match key.kind {
+                        ExprKind::Atom(Atom::String(key) | Atom::Symbol(key)) => Ok((key, val)),
+                        _ => Err(type_error("a string or a symbol as the key", &key)),
+                    }
+                }
+                _ => Err(type_error("a (key value) pair", &pair)),
+            })
+            .collect()
+    }
+}
+
+/// Tuples are lists of a fixed length.
+macro_rules! impl_tuple {
+    ($len:expr; $($name:ident),+) => {
+        impl<$($name: IntoExpr),+> IntoExpr for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn into_expr(self) -> Expr {
+                let ($($name,)+) = self;
+                ExprKind::List(vec![$($name.into_expr()),+]).into()
+            }
+        }
+
+        impl<$($name: FromExpr),+> FromExpr for ($($name,)+) {
+            fn from_expr(expr: Expr) -> Result<Self, SpressoError> {
+                match expr.kind {
+                    ExprKind::List(exprs) if exprs.len() == $len => {
+                        let mut exprs = exprs.into_iter();
+                        Ok(($($name::from_expr(exprs.next().unwrap())?,)+))
+                    }
+                    _ => Err(type_error(concat!("a list of ", $len, " elements"), &expr)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(1; A);
+impl_tuple!(2; A, B);
+impl_tuple!(3; A, B, C);
+impl_tuple!(4; A, B, C, D);
+impl_tuple!(5; A, B, C, D, E);
+impl_tuple!(6; A, B, C, D, E, F);
+
+/// Return value of a typed function: either a value, or a `Result` of one for functions which
+/// can fail.
+pub trait FnResult {
+    fn into_result(self) -> Result<Expr, SpressoError>;
+}
+
+impl<T: IntoExpr> FnResult for T {
+    fn into_result(self) -> Result<Expr, SpressoError> {
+        Ok(self.into_expr())
+    }
+}
+
+impl<T: IntoExpr> FnResult for Result<T, SpressoError> {
+    fn into_result(self) -> Result<Expr, SpressoError> {
+        self.map(IntoExpr::into_expr)
+    }
+}
+
+/// A Rust function with typed arguments, which can be registered with `Env::register_typed_fn`.
+/// `Args` is the tuple of argument types, it only exists to tell the implementations apart.
+pub trait TypedFn<Args>: 'static {
+    /// Number of arguments the function takes.
+    fn arity(&self) -> usize;
+
+    /// Evaluates the args, converts them and calls the function. The arity is already checked.
+    fn call(&self, name: &str, args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError>;
+}
+
+/// Evaluates an arg and converts it into the type the function wants.
+fn typed_arg<T: FromExpr>(
+    name: &str,
+    position: usize,
+    arg: Expr,
+    env: &mut Env,
+) -> Result<T, SpressoError> {
+    let tokens = arg.get_tokens();
+    let val = execute_single(arg, env)?;
+    T::from_expr(val).map_err(|err| {
+        SpressoError::from(RuntimeError::from(format!(
+            "`{}` argument {}: {}",
+            name,
+            position,
+            err.text()
+        )))
+        .maybe_with_tokens(tokens)
+    })
+}
+
+macro_rules! impl_typed_fn {
+    ($len:expr; $($name:ident),*) => {
+        impl<Func, Ret, $($name),*> TypedFn<($($name,)*)> for Func
+        where
+            Func: Fn($($name),*) -> Ret + 'static,
+            Ret: FnResult,
+            $($name: FromExpr,)*
+        {
+            fn arity(&self) -> usize {
+                $len
+            }
+
+            #[allow(unused_variables, unused_mut)]
+            fn call(&self, name: &str, args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+                let mut args = args.into_iter().enumerate();
+                self($({
+                    let (idx, arg) = args.next().unwrap();
+                    typed_arg::<$name>(name, idx + 1, arg, env)?
+                }),*)
+                .into_result()
+            }
+        }
+    };
+}
+
+impl_typed_fn!(0;);
+impl_typed_fn!(1; A);
+impl_typed_fn!(2; A, B);
+impl_typed_fn!(3; A, B, C);
+impl_typed_fn!(4; A, B, C, D);
+impl_typed_fn!(5; A, B, C, D, E);
+impl_typed_fn!(6; A, B, C, D, E, F);
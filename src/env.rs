@@ -1,15 +1,21 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::f64::consts;
+use std::io::{self, BufRead, Write};
 use std::mem;
 use std::ops::Index;
 use std::rc::Rc;
+use std::time::Instant;
 
 use log::debug;
 use slab::Slab;
 
-use crate::ast::{Atom, Expr, ExprKind};
+use crate::ast::{Atom, Expr, ExprKind, Number};
+use crate::convert::{IntoExpr, TypedFn};
 
 use crate::errors::{RuntimeError, SpressoError};
-use crate::eval;
+use crate::eval::{self, check_arity};
+use crate::utils::rng::Rng;
 
 pub type EnvMapType = HashMap<String, Expr>;
 
@@ -18,6 +24,22 @@ pub struct Env {
     scopes: Vec<Rc<usize>>,
     scope_slab: Slab<EnvMapType>,
     gc_enabled: bool,
+    /// Whether integer overflow is an error instead of a promotion to a `BigInt`.
+    strict_arithmetic: bool,
+    /// Whether a string can be used where a number is expected, like in `(+ "1" 2)`.
+    string_coercion: bool,
+    /// Number of symbols generated by `gensym` so far.
+    gensym_count: usize,
+    /// Used by the random builtins. Every env has its own, so that seeding one does not affect
+    /// the others.
+    rng: Rng,
+    /// Where `print`, `display` and friends write to. Stdout by default.
+    output: Box<dyn Write>,
+    /// Where `input` reads from. Stdin when not set, which is read through the buffer shared by
+    /// the whole process, so that input buffered while one env reads is not lost to another.
+    input: Option<Box<dyn BufRead>>,
+    /// When the env was created, the starting point of `monotonic-time`.
+    created_at: Instant,
 }
 
 impl Default for Env {
@@ -34,19 +56,120 @@ impl Env {
         global.insert("*".to_string(), ExprKind::Func(eval::mul).into());
         global.insert("-".to_string(), ExprKind::Func(eval::sub).into());
         global.insert("/".to_string(), ExprKind::Func(eval::div).into());
+        global.insert(
+            "numerator".to_string(),
+            ExprKind::Func(eval::numerator).into(),
+        );
+        global.insert(
+            "denominator".to_string(),
+            ExprKind::Func(eval::denominator).into(),
+        );
+        global.insert(
+            "exact->inexact".to_string(),
+            ExprKind::Func(eval::exact_to_inexact).into(),
+        );
+        global.insert(
+            "inexact->exact".to_string(),
+            ExprKind::Func(eval::inexact_to_exact).into(),
+        );
+
+        // math
+        global.insert("mod".to_string(), ExprKind::Func(eval::modulo).into());
+        global.insert("rem".to_string(), ExprKind::Func(eval::rem).into());
+        global.insert("quot".to_string(), ExprKind::Func(eval::quot).into());
+        global.insert("expt".to_string(), ExprKind::Func(eval::expt).into());
+        global.insert("sqrt".to_string(), ExprKind::Func(eval::sqrt).into());
+        global.insert("exp".to_string(), ExprKind::Func(eval::exp).into());
+        global.insert("log".to_string(), ExprKind::Func(eval::log).into());
+        global.insert("sin".to_string(), ExprKind::Func(eval::sin).into());
+        global.insert("cos".to_string(), ExprKind::Func(eval::cos).into());
+        global.insert("tan".to_string(), ExprKind::Func(eval::tan).into());
+        global.insert("asin".to_string(), ExprKind::Func(eval::asin).into());
+        global.insert("acos".to_string(), ExprKind::Func(eval::acos).into());
+        global.insert("atan".to_string(), ExprKind::Func(eval::atan).into());
+        global.insert("floor".to_string(), ExprKind::Func(eval::floor).into());
+        global.insert("ceil".to_string(), ExprKind::Func(eval::ceil).into());
+        global.insert("round".to_string(), ExprKind::Func(eval::round).into());
+        global.insert(
+            "truncate".to_string(),
+            ExprKind::Func(eval::truncate).into(),
+        );
+        global.insert("abs".to_string(), ExprKind::Func(eval::abs).into());
+        global.insert("min".to_string(), ExprKind::Func(eval::min).into());
+        global.insert("max".to_string(), ExprKind::Func(eval::max).into());
+        global.insert("gcd".to_string(), ExprKind::Func(eval::gcd).into());
+        global.insert("lcm".to_string(), ExprKind::Func(eval::lcm).into());
+        global.insert(
+            "pi".to_string(),
+            ExprKind::Atom(Atom::Number(Number::Float(consts::PI))).into(),
+        );
+        global.insert(
+            "e".to_string(),
+            ExprKind::Atom(Atom::Number(Number::Float(consts::E))).into(),
+        );
+
+        // bitwise operations
+        global.insert("bit-and".to_string(), ExprKind::Func(eval::bit_and).into());
+        global.insert("bit-or".to_string(), ExprKind::Func(eval::bit_or).into());
+        global.insert("bit-xor".to_string(), ExprKind::Func(eval::bit_xor).into());
+        global.insert("bit-not".to_string(), ExprKind::Func(eval::bit_not).into());
+        global.insert(
+            "shift-left".to_string(),
+            ExprKind::Func(eval::shift_left).into(),
+        );
+        global.insert(
+            "shift-right".to_string(),
+            ExprKind::Func(eval::shift_right).into(),
+        );
+        global.insert(
+            "popcount".to_string(),
+            ExprKind::Func(eval::popcount).into(),
+        );
+
+        // random numbers
+        global.insert(
+            "random-seed".to_string(),
+            ExprKind::Func(eval::random_seed).into(),
+        );
+        global.insert(
+            "random-int".to_string(),
+            ExprKind::Func(eval::random_int).into(),
+        );
+        global.insert(
+            "random-float".to_string(),
+            ExprKind::Func(eval::random_float).into(),
+        );
+        global.insert("shuffle".to_string(), ExprKind::Func(eval::shuffle).into());
+        global.insert("choice".to_string(), ExprKind::Func(eval::choice).into());
 
         // keywords
-        global.insert("define".to_string(), ExprKind::Func(eval::define).into());
+        global.insert(
+            "define".to_string(),
+            ExprKind::SpecialForm(eval::define).into(),
+        );
         global.insert("print".to_string(), ExprKind::Func(eval::print).into());
         global.insert("input".to_string(), ExprKind::Func(eval::input).into());
+        global.insert("display".to_string(), ExprKind::Func(eval::display).into());
+        global.insert("format".to_string(), ExprKind::Func(eval::format).into());
+        global.insert("printf".to_string(), ExprKind::Func(eval::printf).into());
+        global.insert("sprintf".to_string(), ExprKind::Func(eval::sprintf).into());
         global.insert("true".to_string(), ExprKind::Atom(Atom::Bool(true)).into());
         global.insert(
             "false".to_string(),
             ExprKind::Atom(Atom::Bool(false)).into(),
         );
-        global.insert("if".to_string(), ExprKind::Func(eval::if_cond).into());
-        global.insert("lambda".to_string(), ExprKind::Func(eval::lambda).into());
-        global.insert("loop".to_string(), ExprKind::Func(eval::while_loop).into());
+        global.insert(
+            "if".to_string(),
+            ExprKind::SpecialForm(eval::if_cond).into(),
+        );
+        global.insert(
+            "lambda".to_string(),
+            ExprKind::SpecialForm(eval::lambda).into(),
+        );
+        global.insert(
+            "loop".to_string(),
+            ExprKind::SpecialForm(eval::while_loop).into(),
+        );
 
         // relational operators
         global.insert(">".to_string(), ExprKind::Func(eval::gt).into());
@@ -55,18 +178,228 @@ impl Env {
         global.insert("<=".to_string(), ExprKind::Func(eval::lteq).into());
         global.insert("==".to_string(), ExprKind::Func(eval::eq).into());
         global.insert("!=".to_string(), ExprKind::Func(eval::neq).into());
+        global.insert("equal?".to_string(), ExprKind::Func(eval::eq).into());
+        global.insert("eq?".to_string(), ExprKind::Func(eval::identical).into());
 
         // logical operators
         global.insert("not".to_string(), ExprKind::Func(eval::not).into());
-        global.insert("and".to_string(), ExprKind::Func(eval::and).into());
-        global.insert("or".to_string(), ExprKind::Func(eval::or).into());
+        global.insert("and".to_string(), ExprKind::SpecialForm(eval::and).into());
+        global.insert("or".to_string(), ExprKind::SpecialForm(eval::or).into());
 
         // lists and their functions
-        global.insert("'".to_string(), ExprKind::Func(eval::list).into());
+        global.insert("'".to_string(), ExprKind::SpecialForm(eval::list).into());
+        global.insert(
+            "quote".to_string(),
+            ExprKind::SpecialForm(eval::list).into(),
+        );
         global.insert("map".to_string(), ExprKind::Func(eval::map).into());
         global.insert("append".to_string(), ExprKind::Func(eval::append).into());
         global.insert("number".to_string(), ExprKind::Func(eval::cast_as_num).into());
 
+        // strings and their functions
+        global.insert(
+            "string-length".to_string(),
+            ExprKind::Func(eval::string_length).into(),
+        );
+        global.insert(
+            "substring".to_string(),
+            ExprKind::Func(eval::substring).into(),
+        );
+        global.insert(
+            "string-append".to_string(),
+            ExprKind::Func(eval::string_append).into(),
+        );
+        global.insert(
+            "concat".to_string(),
+            ExprKind::Func(eval::string_append).into(),
+        );
+        global.insert("split".to_string(), ExprKind::Func(eval::split).into());
+        global.insert("join".to_string(), ExprKind::Func(eval::join).into());
+        global.insert("trim".to_string(), ExprKind::Func(eval::trim).into());
+        global.insert("upcase".to_string(), ExprKind::Func(eval::upcase).into());
+        global.insert(
+            "downcase".to_string(),
+            ExprKind::Func(eval::downcase).into(),
+        );
+        global.insert(
+            "starts-with?".to_string(),
+            ExprKind::Func(eval::starts_with).into(),
+        );
+        global.insert(
+            "ends-with?".to_string(),
+            ExprKind::Func(eval::ends_with).into(),
+        );
+        global.insert(
+            "contains?".to_string(),
+            ExprKind::Func(eval::contains).into(),
+        );
+        global.insert("replace".to_string(), ExprKind::Func(eval::replace).into());
+        global.insert(
+            "index-of".to_string(),
+            ExprKind::Func(eval::index_of).into(),
+        );
+        global.insert("repeat".to_string(), ExprKind::Func(eval::repeat).into());
+        global.insert(
+            "string->number".to_string(),
+            ExprKind::Func(eval::string_to_number).into(),
+        );
+        global.insert(
+            "number->string".to_string(),
+            ExprKind::Func(eval::number_to_string).into(),
+        );
+
+        // symbols
+        global.insert(
+            "symbol?".to_string(),
+            ExprKind::Func(eval::is_symbol).into(),
+        );
+        global.insert(
+            "symbol->string".to_string(),
+            ExprKind::Func(eval::symbol_to_string).into(),
+        );
+        global.insert(
+            "string->symbol".to_string(),
+            ExprKind::Func(eval::string_to_symbol).into(),
+        );
+        global.insert("gensym".to_string(), ExprKind::Func(eval::gensym).into());
+
+        // regular expressions
+        global.insert("regex".to_string(), ExprKind::Func(eval::regex).into());
+        global.insert(
+            "regex-match".to_string(),
+            ExprKind::Func(eval::regex_match).into(),
+        );
+        global.insert(
+            "regex-find".to_string(),
+            ExprKind::Func(eval::regex_find).into(),
+        );
+        global.insert(
+            "regex-find-all".to_string(),
+            ExprKind::Func(eval::regex_find_all).into(),
+        );
+        global.insert(
+            "regex-captures".to_string(),
+            ExprKind::Func(eval::regex_captures).into(),
+        );
+        global.insert(
+            "regex-replace".to_string(),
+            ExprKind::Func(eval::regex_replace).into(),
+        );
+        global.insert(
+            "regex-split".to_string(),
+            ExprKind::Func(eval::regex_split).into(),
+        );
+
+        // json
+        global.insert(
+            "json-parse".to_string(),
+            ExprKind::Func(eval::json_parse).into(),
+        );
+        global.insert(
+            "json-stringify".to_string(),
+            ExprKind::Func(eval::json_stringify).into(),
+        );
+
+        // csv
+        global.insert(
+            "csv-read".to_string(),
+            ExprKind::Func(eval::csv_read).into(),
+        );
+        global.insert(
+            "csv-read-file".to_string(),
+            ExprKind::Func(eval::csv_read_file).into(),
+        );
+        global.insert(
+            "csv-write".to_string(),
+            ExprKind::Func(eval::csv_write).into(),
+        );
+        global.insert(
+            "csv-write-file".to_string(),
+            ExprKind::Func(eval::csv_write_file).into(),
+        );
+
+        // files
+        global.insert(
+            "read-file".to_string(),
+            ExprKind::Func(eval::read_file).into(),
+        );
+        global.insert(
+            "read-lines".to_string(),
+            ExprKind::Func(eval::read_lines).into(),
+        );
+        global.insert(
+            "write-file".to_string(),
+            ExprKind::Func(eval::write_file).into(),
+        );
+        global.insert(
+            "append-file".to_string(),
+            ExprKind::Func(eval::append_file).into(),
+        );
+        global.insert(
+            "list-dir".to_string(),
+            ExprKind::Func(eval::list_dir).into(),
+        );
+        global.insert(
+            "file-exists?".to_string(),
+            ExprKind::Func(eval::file_exists).into(),
+        );
+        global.insert(
+            "delete-file".to_string(),
+            ExprKind::Func(eval::delete_file).into(),
+        );
+        global.insert(
+            "make-dir".to_string(),
+            ExprKind::Func(eval::make_dir).into(),
+        );
+        global.insert(
+            "path-join".to_string(),
+            ExprKind::Func(eval::path_join).into(),
+        );
+        global.insert(
+            "path-basename".to_string(),
+            ExprKind::Func(eval::path_basename).into(),
+        );
+        global.insert(
+            "path-extension".to_string(),
+            ExprKind::Func(eval::path_extension).into(),
+        );
+
+        // processes
+        global.insert(
+            "command-line-args".to_string(),
+            ExprKind::List(Vec::new()).into(),
+        );
+        global.insert("getenv".to_string(), ExprKind::Func(eval::getenv).into());
+        global.insert("setenv".to_string(), ExprKind::Func(eval::setenv).into());
+        global.insert("exit".to_string(), ExprKind::Func(eval::exit).into());
+        global.insert(
+            "run-process".to_string(),
+            ExprKind::Func(eval::run_process).into(),
+        );
+
+        // time
+        global.insert(
+            "current-time".to_string(),
+            ExprKind::Func(eval::current_time).into(),
+        );
+        global.insert(
+            "monotonic-time".to_string(),
+            ExprKind::Func(eval::monotonic_time).into(),
+        );
+        global.insert("sleep".to_string(), ExprKind::Func(eval::sleep).into());
+        global.insert(
+            "elapsed-time".to_string(),
+            ExprKind::SpecialForm(eval::elapsed_time).into(),
+        );
+        global.insert(
+            "format-time".to_string(),
+            ExprKind::Func(eval::format_time).into(),
+        );
+        global.insert(
+            "parse-time".to_string(),
+            ExprKind::Func(eval::parse_time).into(),
+        );
+
         let mut scope_slab = Slab::new();
 
         Env {
@@ -74,9 +407,21 @@ impl Env {
             scopes: Vec::new(),
             scope_slab,
             gc_enabled: true,
+            strict_arithmetic: false,
+            string_coercion: true,
+            gensym_count: 0,
+            rng: Rng::from_entropy(),
+            output: Box::new(io::stdout()),
+            input: None,
+            created_at: Instant::now(),
         }
     }
 
+    /// Starts building an env with options other than the defaults. See [`EnvBuilder`].
+    pub fn builder() -> EnvBuilder {
+        EnvBuilder::default()
+    }
+
     fn scope(&self, index: Rc<usize>) -> &EnvMapType {
         self.scope_slab.get(index).unwrap().0
     }
@@ -103,6 +448,56 @@ impl Env {
         }
     }
 
+    /// Makes the args the program was run with available as `command-line-args`.
+    pub fn set_command_line_args(&mut self, args: Vec<String>) {
+        self.global_scope_mut()
+            .insert("command-line-args".to_string(), args.into_expr());
+    }
+
+    /// Registers a Rust closure as a builtin in the global scope. Unlike the builtins in `eval`,
+    /// the closure can capture state, like a database handle or a counter.
+    ///
+    /// Just like the other builtins, the closure gets the args without evaluating them. Use
+    /// `eval::execute_single` to evaluate an arg.
+    pub fn register_fn<F>(&mut self, name: &str, func: F)
+    where
+        F: Fn(Vec<Expr>, &mut Env) -> Result<Expr, SpressoError> + 'static,
+    {
+        self.global_scope_mut()
+            .insert(name.to_string(), ExprKind::NativeFunc(Rc::new(func)).into());
+    }
+
+    /// Registers a Rust function with typed args, like `fn(i64, String) -> bool`, as a builtin.
+    /// The args are evaluated and converted with `FromExpr`, and the return value with
+    /// `IntoExpr`. Calling it with the wrong number of args or wrong types is an error.
+    pub fn register_typed_fn<Args, F>(&mut self, name: &str, func: F)
+    where
+        F: TypedFn<Args>,
+    {
+        let fn_name = name.to_string();
+        self.register_fn(name, move |args, env| {
+            check_arity(&fn_name, &args, func.arity(), func.arity())?;
+            func.call(&fn_name, args, env)
+        });
+    }
+
+    /// Calls a lambda or a builtin with already evaluated args, so host code can invoke callbacks
+    /// defined in a program. The args are not evaluated again.
+    ///
+    /// Special forms like `define`, `if` or `lambda` take code rather than values, so calling
+    /// one is an error. Evaluate an expression using them instead.
+    pub fn call(&mut self, callable: &Expr, args: Vec<Expr>) -> Result<Expr, SpressoError> {
+        eval::apply(callable, args, self)
+    }
+
+    /// Like [`Env::call`], but calls whatever is bound to `name` in the global scope.
+    pub fn call_global(&mut self, name: &str, args: Vec<Expr>) -> Result<Expr, SpressoError> {
+        let callable = self.global_scope().get(name).cloned().ok_or_else(|| {
+            SpressoError::from(RuntimeError::from(format!("Symbol not found: {}", name)))
+        })?;
+        self.call(&callable, args)
+    }
+
     pub fn contains_key(&self, key: &str) -> bool {
         if self
             .scopes
@@ -155,10 +550,15 @@ impl Env {
         res
     }
 
-    pub fn display(&self) {
-        for (key, value) in self.global_scope() {
-            print!("{}\t:\t{}", key, value);
-        }
+    /// Writes every global binding to the env's output stream.
+    pub fn display(&mut self) -> io::Result<()> {
+        let text: String = self
+            .global_scope()
+            .iter()
+            .map(|(key, value)| format!("{}\t:\t{}", key, value))
+            .collect();
+        self.output.write_all(text.as_bytes())?;
+        self.output.flush()
     }
 
     pub fn get_current_scopes(&self) -> Vec<Rc<usize>> {
@@ -174,6 +574,160 @@ impl Env {
     pub fn disable_gc(&mut self) {
         self.gc_enabled = false;
     }
+
+    /// Makes integer arithmetic which overflows an i64 raise a `NumericError` instead of
+    /// promoting the result to a `BigInt`.
+    pub fn enable_strict_arithmetic(&mut self) {
+        self.strict_arithmetic = true;
+    }
+
+    /// Whether integer results which overflow an i64 are an error. See
+    /// [`Env::enable_strict_arithmetic`].
+    pub fn is_strict_arithmetic(&self) -> bool {
+        self.strict_arithmetic
+    }
+
+    /// Makes using a string where a number is expected, like in `(+ "1" 2)`, an error instead of
+    /// converting the string. `number` can still be used to convert explicitly. Comparisons like
+    /// `(< "10" 9)` never convert, they are always an error.
+    pub fn disable_string_coercion(&mut self) {
+        self.string_coercion = false;
+    }
+
+    pub fn is_string_coercion_enabled(&self) -> bool {
+        self.string_coercion
+    }
+
+    /// Seeds the random number generator, so that the random builtins give the same sequence
+    /// of results every time.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    pub(crate) fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub(crate) fn output(&mut self) -> &mut dyn Write {
+        &mut self.output
+    }
+
+    /// Reads a line from the env's input stream into `buf`, like [`BufRead::read_line`].
+    pub(crate) fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+        match &mut self.input {
+            Some(input) => input.read_line(buf),
+            None => io::stdin().read_line(buf),
+        }
+    }
+
+    pub(crate) fn created_at(&self) -> Instant {
+        self.created_at
+    }
+
+    /// Returns a number that has not been returned before by this env. Used to generate unique
+    /// symbols.
+    pub fn next_gensym_id(&mut self) -> usize {
+        self.gensym_count += 1;
+        self.gensym_count
+    }
+}
+
+/// Builds an `Env` with options other than the defaults, like a different output stream.
+/// # Usage
+/// ```
+/// use spressolisp::env::{CapturedOutput, Env};
+///
+/// let output = CapturedOutput::new();
+/// let env = Env::builder()
+///     .output(output.clone())
+///     .input(&b"some input"[..])
+///     .strict_arithmetic()
+///     .build();
+/// ```
+#[derive(Default)]
+pub struct EnvBuilder {
+    output: Option<Box<dyn Write>>,
+    input: Option<Box<dyn BufRead>>,
+    strict_arithmetic: bool,
+    disable_string_coercion: bool,
+    random_seed: Option<u64>,
+}
+
+impl EnvBuilder {
+    pub fn output(mut self, output: impl Write + 'static) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    pub fn input(mut self, input: impl BufRead + 'static) -> Self {
+        self.input = Some(Box::new(input));
+        self
+    }
+
+    /// See [`Env::enable_strict_arithmetic`].
+    pub fn strict_arithmetic(mut self) -> Self {
+        self.strict_arithmetic = true;
+        self
+    }
+
+    /// See [`Env::disable_string_coercion`].
+    pub fn disable_string_coercion(mut self) -> Self {
+        self.disable_string_coercion = true;
+        self
+    }
+
+    /// See [`Env::set_random_seed`].
+    pub fn random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Env {
+        let mut env = Env::new();
+        if let Some(output) = self.output {
+            env.output = output;
+        }
+        env.input = self.input;
+        if self.strict_arithmetic {
+            env.enable_strict_arithmetic();
+        }
+        if self.disable_string_coercion {
+            env.disable_string_coercion();
+        }
+        if let Some(seed) = self.random_seed {
+            env.set_random_seed(seed);
+        }
+        env
+    }
+}
+
+/// An output stream which keeps everything written to it in memory, to check what a program
+/// printed. Clones share the same buffer, so one clone can be given to an `Env` and the other
+/// used to read the output.
+#[derive(Clone, Default)]
+pub struct CapturedOutput {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.borrow()).into_owned()
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
 impl Index<&str> for Env {
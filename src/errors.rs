@@ -1,4 +1,5 @@
 use std::fmt;
+use std::io;
 
 use colored::Colorize;
 
@@ -15,6 +16,10 @@ pub enum SpressoErrorType {
     Runtime(RuntimeError),
     Syntax(SyntaxError),
     Numeric(NumericError),
+    Io(IoError),
+    /// Not really an error: the program asked to exit with a status code, using `exit`. It
+    /// stops the evaluation just like an error does.
+    Exit(ExitRequest),
 }
 
 impl SpressoError {
@@ -32,6 +37,8 @@ impl SpressoError {
             SpressoErrorType::Runtime(err) => err.err.as_str(),
             SpressoErrorType::Syntax(err) => err.err.as_str(),
             SpressoErrorType::Numeric(err) => err.err.as_str(),
+            SpressoErrorType::Io(err) => err.err.as_str(),
+            SpressoErrorType::Exit(exit) => exit.err.as_str(),
         }
     }
 
@@ -42,6 +49,16 @@ impl SpressoError {
             SpressoErrorType::Runtime(..) => "Runtime Error",
             SpressoErrorType::Syntax(..) => "Syntax Error",
             SpressoErrorType::Numeric(..) => "Numeric Error",
+            SpressoErrorType::Io(..) => "IO Error",
+            SpressoErrorType::Exit(..) => "Exit",
+        }
+    }
+
+    /// The status code, if the program asked to exit instead of running into an error.
+    pub fn exit_code(&self) -> Option<i32> {
+        match &self.detail {
+            SpressoErrorType::Exit(exit) => Some(exit.code),
+            _ => None,
         }
     }
 }
@@ -92,6 +109,18 @@ impl From<NumericError> for SpressoError {
     }
 }
 
+impl From<ExitRequest> for SpressoError {
+    fn from(exit: ExitRequest) -> Self {
+        SpressoError::new(SpressoErrorType::Exit(exit))
+    }
+}
+
+impl From<IoError> for SpressoError {
+    fn from(err: IoError) -> Self {
+        SpressoError::new(SpressoErrorType::Io(err))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RuntimeError {
     pub err: String,
@@ -160,3 +189,44 @@ impl fmt::Display for NumericError {
         write!(f, "Numeric Error: {}", self.err)
     }
 }
+
+/// An error from the OS while working with files, directories or processes.
+#[derive(Debug, Clone)]
+pub struct IoError {
+    pub err: String,
+    pub kind: io::ErrorKind,
+    /// The error code from the OS, if there was one.
+    pub os_code: Option<i32>,
+}
+
+impl IoError {
+    /// `context` says what was being done, like "read-file: could not read `a.txt`".
+    pub fn new(context: impl fmt::Display, source: &io::Error) -> Self {
+        IoError {
+            err: format!("{}: {}", context, source),
+            kind: source.kind(),
+            os_code: source.raw_os_error(),
+        }
+    }
+}
+
+impl fmt::Display for IoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IO Error: {}", self.err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExitRequest {
+    pub code: i32,
+    pub err: String,
+}
+
+impl From<i32> for ExitRequest {
+    fn from(code: i32) -> Self {
+        ExitRequest {
+            code,
+            err: format!("exited with status code {}", code),
+        }
+    }
+}
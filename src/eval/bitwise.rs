@@ -0,0 +1,123 @@
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{NumericError, SpressoError},
+    eval::{check_arity, check_overflow, check_result_bits, extract_integer},
+    TokenGiver, TokenHoarder,
+};
+
+fn int_expr(num: BigInt) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Number(Number::from_bigint(num))))
+}
+
+/// Folds integer args with a bitwise `op`, starting from `init`.
+fn bitwise_fold(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    init: BigInt,
+    op: fn(BigInt, BigInt) -> BigInt,
+) -> Result<Expr, SpressoError> {
+    let mut res = init;
+    for arg in args {
+        res = op(res, extract_integer(name, arg, env)?);
+    }
+    Ok(int_expr(res))
+}
+
+/// Evaluates the args of a `(<fn> num amount)` call.
+/// `amount` has to be a non-negative integer which is small enough to be sensible.
+fn shift_args(name: &str, args: &[Expr], env: &mut Env) -> Result<(BigInt, u32), SpressoError> {
+    check_arity(name, args, 2, 2)?;
+    let num = extract_integer(name, args[0].clone(), env)?;
+    let amount = extract_integer(name, args[1].clone(), env)?;
+
+    if amount.is_negative() {
+        return Err(SpressoError::from(NumericError::from(format!(
+            "{}: expected a non-negative shift amount, got: {}",
+            name, amount
+        )))
+        .maybe_with_tokens(args[1].get_tokens()));
+    }
+    match amount.to_u32() {
+        Some(amount) => Ok((num, amount)),
+        None => Err(SpressoError::from(NumericError::from(format!(
+            "{}: shift amount {} is too large",
+            name, amount
+        )))
+        .maybe_with_tokens(args[1].get_tokens())),
+    }
+}
+
+/// Bitwise and of any number of integers. Negative integers behave like they are in two's
+/// complement.
+/// # Usage
+/// `(bit-and 12 10)`
+pub fn bit_and(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    bitwise_fold("bit-and", args, env, BigInt::from(-1), |x, y| x & y)
+}
+
+/// Bitwise or of any number of integers.
+/// # Usage
+/// `(bit-or 12 10)`
+pub fn bit_or(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    bitwise_fold("bit-or", args, env, BigInt::from(0), |x, y| x | y)
+}
+
+/// Bitwise exclusive or of any number of integers.
+/// # Usage
+/// `(bit-xor 12 10)`
+pub fn bit_xor(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    bitwise_fold("bit-xor", args, env, BigInt::from(0), |x, y| x ^ y)
+}
+
+/// Flips all the bits, so `(bit-not n)` is `-n - 1`.
+/// # Usage
+/// `(bit-not 12)`
+pub fn bit_not(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("bit-not", &args, 1, 1)?;
+    let num = extract_integer("bit-not", args[0].clone(), env)?;
+    Ok(int_expr(!num))
+}
+
+/// Shifts the bits to the left, which is the same as multiplying by 2^amount.
+/// # Usage
+/// `(shift-left 1 8)`
+pub fn shift_left(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (num, amount) = shift_args("shift-left", &args, env)?;
+    if !num.is_zero() {
+        check_result_bits("shift-left", num.bits() + u64::from(amount), true, env)
+            .map_err(|err| err.maybe_with_tokens(args.get_tokens()))?;
+    }
+    let res = check_overflow(Number::from_bigint(num << amount), env)
+        .map_err(|err| err.maybe_with_tokens(args.get_tokens()))?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(res))))
+}
+
+/// Shifts the bits to the right, keeping the sign. Same as dividing by 2^amount and rounding
+/// down.
+/// # Usage
+/// `(shift-right 256 4)`
+pub fn shift_right(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (num, amount) = shift_args("shift-right", &args, env)?;
+    Ok(int_expr(num >> amount))
+}
+
+/// Number of bits set in a non-negative integer.
+/// # Usage
+/// `(popcount 255)`
+pub fn popcount(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("popcount", &args, 1, 1)?;
+    let num = extract_integer("popcount", args[0].clone(), env)?;
+    if num.is_negative() {
+        return Err(SpressoError::from(NumericError::from(format!(
+            "popcount: expected a non-negative integer, got: {}",
+            num
+        )))
+        .maybe_with_tokens(args[0].get_tokens()));
+    }
+    Ok(int_expr(BigInt::from(num.magnitude().count_ones())))
+}
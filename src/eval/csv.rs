@@ -0,0 +1,233 @@
+use std::fs;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind},
+    env::Env,
+    errors::{IoError, RuntimeError, SpressoError},
+    eval::{
+        check_arity, execute_single, extract_bool, extract_string,
+        json::{as_object, object_pairs, OBJECT_TAG},
+    },
+    TokenGiver, TokenHoarder,
+};
+
+fn csv_error(name: &str, err: impl std::fmt::Display) -> SpressoError {
+    SpressoError::from(RuntimeError::from(format!("{}: {}", name, err)))
+}
+
+fn string_expr(string: String) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::String(string)))
+}
+
+/// Parses csv text into a list of rows, each a list of strings. With headers, the first row
+/// names the columns and every other row becomes an object of `(column value)` pairs, like
+/// the ones `json-parse` returns.
+fn parse_csv(name: &str, text: &str, headers: bool) -> Result<Expr, SpressoError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        // rows of different lengths are fine without headers, there is nothing to match up
+        .flexible(!headers)
+        .from_reader(text.as_bytes());
+
+    let mut rows = Vec::new();
+    let mut header: Option<Vec<String>> = None;
+    for record in reader.records() {
+        let record = record.map_err(|err| csv_error(name, err))?;
+        let fields = record.iter().map(|field| string_expr(field.to_string()));
+
+        match &header {
+            None if headers => header = Some(record.iter().map(str::to_string).collect()),
+            None => rows.push(Expr::from(ExprKind::List(fields.collect()))),
+            Some(columns) => {
+                let mut object = vec![Expr::from(ExprKind::Atom(Atom::Symbol(
+                    OBJECT_TAG.to_string(),
+                )))];
+                object.extend(columns.iter().zip(fields).map(|(column, field)| {
+                    Expr::from(ExprKind::List(vec![string_expr(column.clone()), field]))
+                }));
+                rows.push(Expr::from(ExprKind::List(object)));
+            }
+        }
+    }
+
+    Ok(Expr::from(ExprKind::List(rows)))
+}
+
+/// Evaluates the optional headers flag of `csv-read` and `csv-read-file`.
+fn extract_headers(name: &str, args: &[Expr], env: &mut Env) -> Result<bool, SpressoError> {
+    match args.get(1) {
+        Some(arg) => extract_bool(name, arg.clone(), env),
+        None => Ok(false),
+    }
+}
+
+/// Parses csv text into a list of rows, each a list of strings. When the second arg is true,
+/// the first row is a header and every other row becomes an object of `(column value)` pairs.
+/// # Usage
+/// `(csv-read "name,age\nspresso,1" true)`
+pub fn csv_read(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("csv-read", &args, 1, 2)?;
+    let text = extract_string(args[0].clone(), env)?;
+    let headers = extract_headers("csv-read", &args, env)?;
+    parse_csv("csv-read", &text, headers).map_err(|err| err.maybe_with_tokens(args.get_tokens()))
+}
+
+/// Like `csv-read`, but reads the csv from a file.
+/// # Usage
+/// `(csv-read-file "data.csv" true)`
+pub fn csv_read_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("csv-read-file", &args, 1, 2)?;
+    let path = extract_string(args[0].clone(), env)?;
+    let headers = extract_headers("csv-read-file", &args, env)?;
+    let text = fs::read_to_string(&path).map_err(|err| {
+        SpressoError::from(IoError::new(
+            format!("csv-read-file: could not read `{}`", path),
+            &err,
+        ))
+        .maybe_with_tokens(args[0].get_tokens())
+    })?;
+    parse_csv("csv-read-file", &text, headers)
+        .map_err(|err| err.maybe_with_tokens(args.get_tokens()))
+}
+
+/// Text of a single cell. Only atoms can be cells, unit is an empty cell.
+fn cell(name: &str, expr: &Expr) -> Result<String, SpressoError> {
+    match &expr.kind {
+        ExprKind::Atom(Atom::Unit) => Ok(String::new()),
+        ExprKind::Atom(_) => Ok(expr.to_plain_string()),
+        kind => Err(csv_error(
+            name,
+            format!("a {} cannot be a cell: {}", kind.type_name(), expr),
+        )),
+    }
+}
+
+/// The `(column value)` pairs of a row, which is either an object or an untagged list of pairs.
+/// `None` for a row of cells.
+fn row_pairs<'a>(
+    name: &str,
+    items: &'a [Expr],
+) -> Result<Option<Vec<(&'a str, &'a Expr)>>, SpressoError> {
+    match object_pairs(name, items)? {
+        Some(pairs) => Ok(Some(pairs)),
+        None => Ok(as_object(items)),
+    }
+}
+
+/// The records to write for a list of rows. Rows are either lists of cells, or objects or lists
+/// of `(column value)` pairs. With pairs, the columns of the first row become the header.
+fn records(name: &str, rows: &Expr) -> Result<Vec<Vec<String>>, SpressoError> {
+    let rows = match &rows.kind {
+        ExprKind::List(rows) => rows.as_slice(),
+        ExprKind::Atom(Atom::Unit) => &[],
+        _ => {
+            return Err(csv_error(
+                name,
+                format!("expected a list of rows, got: {}", rows),
+            ))
+        }
+    };
+
+    let row_items = |row: &Expr| match &row.kind {
+        ExprKind::List(items) => Ok(items.clone()),
+        ExprKind::Atom(Atom::Unit) => Ok(Vec::new()),
+        _ => Err(csv_error(
+            name,
+            format!("expected a row to be a list, got: {}", row),
+        )),
+    };
+
+    let first = match rows.first() {
+        Some(first) => row_items(first)?,
+        None => return Ok(Vec::new()),
+    };
+    let columns: Option<Vec<String>> = row_pairs(name, &first)?
+        .map(|pairs| pairs.iter().map(|(key, _)| key.to_string()).collect());
+
+    let mut records = Vec::new();
+    if let Some(columns) = &columns {
+        records.push(columns.clone());
+    }
+
+    for row in rows {
+        let items = row_items(row)?;
+        let record = match &columns {
+            None => items
+                .iter()
+                .map(|item| cell(name, item))
+                .collect::<Result<_, _>>()?,
+            Some(columns) => {
+                let pairs = row_pairs(name, &items)?.ok_or_else(|| {
+                    csv_error(
+                        name,
+                        format!("expected a row of (column value) pairs, got: {}", row),
+                    )
+                })?;
+                if let Some((key, _)) = pairs
+                    .iter()
+                    .find(|(key, _)| !columns.iter().any(|c| c == key))
+                {
+                    return Err(csv_error(
+                        name,
+                        format!("column `{}` is not in the header", key),
+                    ));
+                }
+                columns
+                    .iter()
+                    .map(|column| match pairs.iter().find(|(key, _)| key == column) {
+                        Some((_, value)) => cell(name, value),
+                        // a missing column is an empty cell
+                        None => Ok(String::new()),
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn write_csv(name: &str, rows: &Expr) -> Result<String, SpressoError> {
+    let mut writer = ::csv::WriterBuilder::new()
+        .flexible(true)
+        .from_writer(Vec::new());
+    for record in records(name, rows)? {
+        writer
+            .write_record(&record)
+            .map_err(|err| csv_error(name, err))?;
+    }
+    let bytes = writer.into_inner().map_err(|err| csv_error(name, err))?;
+    String::from_utf8(bytes).map_err(|err| csv_error(name, err))
+}
+
+/// Converts a list of rows into csv text, quoting cells when needed. Rows are either lists of
+/// cells, or objects of `(column value)` pairs, like the ones `csv-read` returns with headers.
+/// # Usage
+/// `(csv-write ('(("name" "age") ("spresso" 1))))`
+pub fn csv_write(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("csv-write", &args, 1, 1)?;
+    let rows = execute_single(args[0].clone(), env)?;
+    let text =
+        write_csv("csv-write", &rows).map_err(|err| err.maybe_with_tokens(args.get_tokens()))?;
+    Ok(string_expr(text))
+}
+
+/// Like `csv-write`, but writes the csv into a file.
+/// # Usage
+/// `(csv-write-file "data.csv" rows)`
+pub fn csv_write_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("csv-write-file", &args, 2, 2)?;
+    let path = extract_string(args[0].clone(), env)?;
+    let rows = execute_single(args[1].clone(), env)?;
+    let text = write_csv("csv-write-file", &rows)
+        .map_err(|err| err.maybe_with_tokens(args[1].get_tokens()))?;
+    fs::write(&path, text).map_err(|err| {
+        SpressoError::from(IoError::new(
+            format!("csv-write-file: could not write `{}`", path),
+            &err,
+        ))
+        .maybe_with_tokens(args[0].get_tokens())
+    })?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
+}
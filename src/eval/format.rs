@@ -0,0 +1,410 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{execute_single, extract_string, write_output},
+    TokenGiver, TokenHoarder,
+};
+
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// How a single value should be formatted. Both `{:...}` in `format` and `%...` in `printf`
+/// are parsed into this.
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    plus: bool,
+    zero: bool,
+    width: Option<usize>,
+    precision: Option<usize>,
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Spec {
+            fill: ' ',
+            align: None,
+            plus: false,
+            zero: false,
+            width: None,
+            precision: None,
+        }
+    }
+}
+
+fn format_err(message: String) -> SpressoError {
+    SpressoError::from(RuntimeError::from(message))
+}
+
+/// Largest width or precision in a spec, the same limit as Rust's own formatting. Padding is
+/// built in memory, so an unbounded width could exhaust it.
+const MAX_WIDTH: usize = u16::MAX as usize;
+
+/// Takes the digits of a width or a precision, if there are any.
+fn take_number(chars: &mut Peekable<Chars>, name: &str) -> Result<Option<usize>, SpressoError> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+        digits.push(*c);
+        chars.next();
+    }
+    if digits.is_empty() {
+        return Ok(None);
+    }
+    match digits.parse() {
+        Ok(num) if num <= MAX_WIDTH => Ok(Some(num)),
+        _ => Err(format_err(format!(
+            "{}: {} is too large for a width or precision, the most is {}",
+            name, digits, MAX_WIDTH
+        ))),
+    }
+}
+
+/// Parses the part after `:` in a `{:...}` placeholder.
+/// Follows Rust's syntax: `[[fill]align][+][0][width][.precision]`.
+fn parse_format_spec(text: &str) -> Result<Spec, SpressoError> {
+    let mut spec = Spec::default();
+    let as_align = |c: char| match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        _ => None,
+    };
+
+    let mut chars = text.chars().peekable();
+    let first_two: Vec<char> = text.chars().take(2).collect();
+    if let Some(align) = first_two.get(1).copied().and_then(as_align) {
+        spec.fill = first_two[0];
+        spec.align = Some(align);
+        chars.nth(1);
+    } else if let Some(align) = first_two.first().copied().and_then(as_align) {
+        spec.align = Some(align);
+        chars.next();
+    }
+
+    spec.plus = chars.next_if_eq(&'+').is_some();
+    spec.zero = chars.next_if_eq(&'0').is_some();
+    spec.width = take_number(&mut chars, "format")?;
+    if chars.next_if_eq(&'.').is_some() {
+        spec.precision =
+            Some(take_number(&mut chars, "format")?.ok_or_else(|| {
+                format_err(format!("format: missing precision in {{:{}}}", text))
+            })?);
+    }
+
+    if chars.next().is_some() {
+        return Err(format_err(format!(
+            "format: invalid format spec {{:{}}}",
+            text
+        )));
+    }
+
+    Ok(spec)
+}
+
+/// Pads `body` (which has the sign separately in `sign`) to the width in the spec.
+fn pad(sign: &str, body: String, spec: &Spec, is_number: bool) -> String {
+    let len = sign.chars().count() + body.chars().count();
+    let width = match spec.width {
+        Some(width) if width > len => width,
+        _ => return format!("{}{}", sign, body),
+    };
+    let padding = width - len;
+
+    // zero padding goes between the sign and the digits
+    if spec.zero && is_number {
+        return format!("{}{}{}", sign, "0".repeat(padding), body);
+    }
+
+    let fill = |n: usize| spec.fill.to_string().repeat(n);
+    let default_align = if is_number { Align::Right } else { Align::Left };
+    match spec.align.as_ref().unwrap_or(&default_align) {
+        Align::Left => format!("{}{}{}", sign, body, fill(padding)),
+        Align::Right => format!("{}{}{}", fill(padding), sign, body),
+        Align::Center => format!(
+            "{}{}{}{}",
+            fill(padding / 2),
+            sign,
+            body,
+            fill(padding - padding / 2)
+        ),
+    }
+}
+
+/// Splits the text of a number into its sign and digits, adding a `+` if asked for.
+fn split_sign(text: String, spec: &Spec) -> (&'static str, String) {
+    match text.strip_prefix('-') {
+        Some(digits) => ("-", digits.to_string()),
+        None if spec.plus => ("+", text),
+        None => ("", text),
+    }
+}
+
+/// Formats a number. Precision is the number of digits after the decimal point, which turns an
+/// integer into a float.
+fn format_number(num: &Number, spec: &Spec) -> String {
+    let text = match spec.precision {
+        Some(precision) => format!("{:.*}", precision, num.to_f64()),
+        None => num.to_string(),
+    };
+    let (sign, digits) = split_sign(text, spec);
+    pad(sign, digits, spec, true)
+}
+
+/// Formats any value. Precision truncates non-numbers to that many characters.
+fn format_value(value: &Expr, spec: &Spec) -> String {
+    match &value.kind {
+        ExprKind::Atom(Atom::Number(num)) => format_number(num, spec),
+        _ => {
+            let text = value.to_plain_string();
+            let text = match spec.precision {
+                Some(precision) => text.chars().take(precision).collect(),
+                None => text,
+            };
+            pad("", text, spec, false)
+        }
+    }
+}
+
+/// Evaluates the template and the values to be filled into it.
+fn template_and_values(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+) -> Result<(String, Vec<Expr>), SpressoError> {
+    if args.is_empty() {
+        return Err(format_err(format!("`{}` needs a template string", name)));
+    }
+
+    let mut args = args;
+    let template = extract_string(args.remove(0), env)?;
+    let values: Result<Vec<Expr>, SpressoError> = args
+        .into_iter()
+        .map(|arg| execute_single(arg, env))
+        .collect();
+    Ok((template, values?))
+}
+
+fn next_value<'a>(
+    values: &mut impl Iterator<Item = &'a Expr>,
+    name: &str,
+) -> Result<&'a Expr, SpressoError> {
+    values
+        .next()
+        .ok_or_else(|| format_err(format!("{}: not enough values for the template", name)))
+}
+
+fn fill_template(template: &str, values: &[Expr]) -> Result<String, SpressoError> {
+    let mut res = String::new();
+    let mut values = values.iter();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.next_if_eq(&'{').is_some() => res.push('{'),
+            '}' if chars.next_if_eq(&'}').is_some() => res.push('}'),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(format_err("format: unclosed { in template".to_string()))
+                        }
+                    }
+                }
+
+                let spec = if placeholder.is_empty() {
+                    Spec::default()
+                } else if let Some(spec) = placeholder.strip_prefix(':') {
+                    parse_format_spec(spec)?
+                } else {
+                    return Err(format_err(format!(
+                        "format: invalid placeholder {{{}}}, expected {{}} or {{:spec}}",
+                        placeholder
+                    )));
+                };
+                res.push_str(&format_value(next_value(&mut values, "format")?, &spec));
+            }
+            '}' => return Err(format_err("format: unmatched } in template".to_string())),
+            _ => res.push(c),
+        }
+    }
+
+    if values.next().is_some() {
+        return Err(format_err(
+            "format: more values than placeholders in the template".to_string(),
+        ));
+    }
+
+    Ok(res)
+}
+
+/// Formats a single `%` directive of `printf`.
+fn format_directive(
+    name: &str,
+    conversion: char,
+    value: &Expr,
+    spec: &Spec,
+) -> Result<String, SpressoError> {
+    let num = match &value.kind {
+        ExprKind::Atom(Atom::Number(num)) => Some(num),
+        _ => None,
+    };
+    let expected = |kind: &str| {
+        format_err(format!(
+            "{}: %{} expects {}, got: {}",
+            name,
+            conversion,
+            kind,
+            value.to_plain_string()
+        ))
+        .maybe_with_tokens(value.get_tokens())
+    };
+
+    match conversion {
+        'd' | 'i' => match num.and_then(Number::to_bigint) {
+            Some(num) => {
+                let (sign, digits) = split_sign(num.to_string(), spec);
+                Ok(pad(sign, digits, spec, true))
+            }
+            _ => Err(expected("an integer")),
+        },
+        'x' | 'X' | 'o' => match num.and_then(Number::to_bigint) {
+            Some(num) => {
+                let magnitude = num.magnitude();
+                let digits = match conversion {
+                    'x' => format!("{:x}", magnitude),
+                    'X' => format!("{:X}", magnitude),
+                    _ => format!("{:o}", magnitude),
+                };
+                let (sign, _) = split_sign(num.to_string(), spec);
+                Ok(pad(sign, digits, spec, true))
+            }
+            _ => Err(expected("an integer")),
+        },
+        'f' => match num {
+            Some(num) => {
+                let text = format!("{:.*}", spec.precision.unwrap_or(6), num.to_f64());
+                let (sign, digits) = split_sign(text, spec);
+                Ok(pad(sign, digits, spec, true))
+            }
+            _ => Err(expected("a number")),
+        },
+        's' => Ok(format_value(value, spec)),
+        _ => Err(format_err(format!(
+            "{}: unknown conversion %{}",
+            name, conversion
+        ))),
+    }
+}
+
+/// Fills a C style template: `%[flags][width][.precision]conversion`, where flags are any of
+/// `-` (left align), `+` and `0`, and conversion is one of `d`, `i`, `f`, `s`, `x`, `X` or `o`.
+fn fill_printf_template(
+    name: &str,
+    template: &str,
+    values: &[Expr],
+) -> Result<String, SpressoError> {
+    let mut res = String::new();
+    let mut values = values.iter();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+        if chars.next_if_eq(&'%').is_some() {
+            res.push('%');
+            continue;
+        }
+
+        let mut spec = Spec::default();
+        while let Some(flag) = chars.next_if(|c| matches!(c, '-' | '+' | '0')) {
+            match flag {
+                '-' => spec.align = Some(Align::Left),
+                '+' => spec.plus = true,
+                _ => spec.zero = true,
+            }
+        }
+        // left alignment wins over zero padding
+        spec.zero = spec.zero && spec.align.is_none();
+        spec.width = take_number(&mut chars, name)?;
+        if chars.next_if_eq(&'.').is_some() {
+            spec.precision = Some(take_number(&mut chars, name)?.unwrap_or(0));
+        }
+
+        let conversion = chars
+            .next()
+            .ok_or_else(|| format_err(format!("{}: template ends in the middle of a %", name)))?;
+        let value = next_value(&mut values, name)?;
+        res.push_str(&format_directive(name, conversion, value, &spec)?);
+    }
+
+    if values.next().is_some() {
+        return Err(format_err(format!(
+            "{}: more values than directives in the template",
+            name
+        )));
+    }
+
+    Ok(res)
+}
+
+/// Fills the `{}` placeholders in a template string with values.
+/// A placeholder can have a spec like Rust's: `{:>8.2}`, `{:*^10}`, `{:+05}`.
+/// Use `{{` and `}}` for literal braces.
+/// # Usage
+/// `(format "{} is {:.2}" "pi" 3.14159)`
+pub fn format(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let tokens = args.get_tokens();
+    let (template, values) = template_and_values("format", args, env)?;
+    let res = fill_template(&template, &values).map_err(|err| err.maybe_with_tokens(tokens))?;
+    Ok(Expr::from(ExprKind::Atom(Atom::String(res))))
+}
+
+/// Fills the template of `sprintf` or `printf`, whose name is used in errors.
+fn printf_string(name: &str, args: Vec<Expr>, env: &mut Env) -> Result<String, SpressoError> {
+    let tokens = args.get_tokens();
+    let (template, values) = template_and_values(name, args, env)?;
+    fill_printf_template(name, &template, &values).map_err(|err| err.maybe_with_tokens(tokens))
+}
+
+/// Like `printf`, but returns the string instead of printing it.
+/// # Usage
+/// `(sprintf "%05.1f|%-4d|%s" 3.14159 42 "hi")`
+pub fn sprintf(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let res = printf_string("sprintf", args, env)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::String(res))))
+}
+
+/// Prints a C style template filled with values. Does not add a newline.
+/// # Usage
+/// `(printf "%d items cost %.2f\n" 3 4.5)`
+pub fn printf(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let res = printf_string("printf", args, env)?;
+    write_output(env, &res)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
+}
+
+/// Prints its arguments one after the other followed by a newline. Unlike `print`, strings are
+/// printed without quotes.
+/// # Usage
+/// `(display "x is " x)`
+pub fn display(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let mut res = String::new();
+    for arg in args {
+        res.push_str(&execute_single(arg, env)?.to_plain_string());
+    }
+    res.push('\n');
+    write_output(env, &res)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
+}
@@ -0,0 +1,173 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ast::{Atom, Expr, ExprKind},
+    env::Env,
+    errors::{IoError, SpressoError},
+    eval::{check_arity, check_min_arity, extract_string},
+    TokenGiver, TokenHoarder,
+};
+
+fn string_expr(string: String) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::String(string)))
+}
+
+fn unit_expr() -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Unit))
+}
+
+/// Converts an error from the OS, marking the whole call.
+fn io_error(name: &str, action: &str, path: &str, err: io::Error, args: &[Expr]) -> SpressoError {
+    SpressoError::from(IoError::new(
+        format!("{}: could not {} `{}`", name, action, path),
+        &err,
+    ))
+    .maybe_with_tokens(args.get_tokens())
+}
+
+/// Evaluates the only arg of a `(<fn> path)` call.
+fn path_arg(name: &str, args: &[Expr], env: &mut Env) -> Result<String, SpressoError> {
+    check_arity(name, args, 1, 1)?;
+    extract_string(args[0].clone(), env)
+}
+
+/// Evaluates both args of a `(<fn> path contents)` call.
+fn path_and_contents(
+    name: &str,
+    args: &[Expr],
+    env: &mut Env,
+) -> Result<(String, String), SpressoError> {
+    check_arity(name, args, 2, 2)?;
+    let path = extract_string(args[0].clone(), env)?;
+    let contents = extract_string(args[1].clone(), env)?;
+    Ok((path, contents))
+}
+
+/// Reads a whole file into a string.
+/// # Usage
+/// `(read-file "notes.txt")`
+pub fn read_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("read-file", &args, env)?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|err| io_error("read-file", "read", &path, err, &args))?;
+    Ok(string_expr(contents))
+}
+
+/// Reads a file into a list of its lines, without the line endings.
+/// # Usage
+/// `(read-lines "notes.txt")`
+pub fn read_lines(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("read-lines", &args, env)?;
+    let contents = fs::read_to_string(&path)
+        .map_err(|err| io_error("read-lines", "read", &path, err, &args))?;
+    let lines = contents
+        .lines()
+        .map(|line| string_expr(line.to_string()))
+        .collect();
+    Ok(Expr::from(ExprKind::List(lines)))
+}
+
+/// Writes a string into a file, replacing what was in it. The file is created if needed.
+/// # Usage
+/// `(write-file "notes.txt" "hello")`
+pub fn write_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (path, contents) = path_and_contents("write-file", &args, env)?;
+    fs::write(&path, contents).map_err(|err| io_error("write-file", "write", &path, err, &args))?;
+    Ok(unit_expr())
+}
+
+/// Adds a string to the end of a file. The file is created if needed.
+/// # Usage
+/// `(append-file "log.txt" "one more line\n")`
+pub fn append_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (path, contents) = path_and_contents("append-file", &args, env)?;
+    OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|err| io_error("append-file", "append to", &path, err, &args))?;
+    Ok(unit_expr())
+}
+
+/// Names of the entries in a directory, sorted.
+/// # Usage
+/// `(list-dir ".")`
+pub fn list_dir(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("list-dir", &args, env)?;
+    let mut names = fs::read_dir(&path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect::<Result<Vec<_>, io::Error>>()
+        })
+        .map_err(|err| io_error("list-dir", "list", &path, err, &args))?;
+    names.sort();
+    Ok(Expr::from(ExprKind::List(
+        names.into_iter().map(string_expr).collect(),
+    )))
+}
+
+/// Whether a file or a directory exists at the path.
+/// # Usage
+/// `(file-exists? "notes.txt")`
+pub fn file_exists(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("file-exists?", &args, env)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Bool(
+        Path::new(&path).exists(),
+    ))))
+}
+
+/// Deletes a file.
+/// # Usage
+/// `(delete-file "notes.txt")`
+pub fn delete_file(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("delete-file", &args, env)?;
+    fs::remove_file(&path).map_err(|err| io_error("delete-file", "delete", &path, err, &args))?;
+    Ok(unit_expr())
+}
+
+/// Creates a directory, along with any missing parent directories.
+/// # Usage
+/// `(make-dir "out/reports")`
+pub fn make_dir(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("make-dir", &args, env)?;
+    fs::create_dir_all(&path).map_err(|err| io_error("make-dir", "create", &path, err, &args))?;
+    Ok(unit_expr())
+}
+
+/// Joins paths with the separator of the OS. An absolute path replaces everything before it.
+/// # Usage
+/// `(path-join "out" "reports" "march.csv")`
+pub fn path_join(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_min_arity("path-join", &args, 1)?;
+    let mut path = PathBuf::new();
+    for arg in args {
+        path.push(extract_string(arg, env)?);
+    }
+    Ok(string_expr(path.to_string_lossy().into_owned()))
+}
+
+/// The last part of a path, or unit if there is none.
+/// # Usage
+/// `(path-basename "out/reports/march.csv")`
+pub fn path_basename(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("path-basename", &args, env)?;
+    Ok(match Path::new(&path).file_name() {
+        Some(name) => string_expr(name.to_string_lossy().into_owned()),
+        None => unit_expr(),
+    })
+}
+
+/// The extension of a path without the dot, or unit if there is none.
+/// # Usage
+/// `(path-extension "march.csv")`
+pub fn path_extension(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let path = path_arg("path-extension", &args, env)?;
+    Ok(match Path::new(&path).extension() {
+        Some(ext) => string_expr(ext.to_string_lossy().into_owned()),
+        None => unit_expr(),
+    })
+}
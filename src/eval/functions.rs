@@ -2,7 +2,7 @@ use crate::{
     ast::{Atom, Expr, ExprKind, Lambda},
     env::Env,
     errors::{RuntimeError, SpressoError},
-    eval::execute_single,
+    eval::{execute_single, list},
     TokenGiver, TokenHoarder,
 };
 
@@ -61,8 +61,11 @@ pub fn execute_lambda(
         .into_iter()
         .map(|arg| execute_single(arg, env))
         .collect();
-    let args = args?;
+    apply_lambda(lambda, args?, env)
+}
 
+/// Like [`execute_lambda`], but the args are already evaluated and are passed as they are.
+pub fn apply_lambda(lambda: Lambda, args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     if args.len() != lambda.params.len() {
         Err(SpressoError::from(RuntimeError::from(format!(
             "Expected {} arguments, got {}",
@@ -101,3 +104,48 @@ pub fn execute_lambda(
         })
     }
 }
+
+/// Calls anything that can be called with already evaluated args: a lambda, a builtin, or a
+/// symbol bound to one of them.
+///
+/// Special forms, like `define`, `if` or `lambda`, cannot be called this way, since their args
+/// are code rather than values. Calling one is an error.
+pub fn apply(callable: &Expr, args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    match &callable.kind {
+        ExprKind::Lambda(lambda) => apply_lambda(lambda.clone(), args, env),
+        ExprKind::SpecialForm(_) => Err(SpressoError::from(RuntimeError::from(format!(
+            "special forms cannot be called with values, only evaluated: {}",
+            callable
+        )))
+        .maybe_with_tokens(callable.get_tokens())),
+        // builtins evaluate their args themselves, so quote them to get them back as they are
+        ExprKind::Func(func) => func(args.into_iter().map(quote).collect(), env),
+        ExprKind::NativeFunc(func) => func(args.into_iter().map(quote).collect(), env),
+        ExprKind::Atom(Atom::Symbol(symbol)) => {
+            let value = env
+                .get_symbol(symbol.as_str())
+                .maybe_with_tokens(callable.get_tokens())?;
+            apply(&value, args, env)
+        }
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "this is not something I can execute: {}",
+            callable
+        )))
+        .maybe_with_tokens(callable.get_tokens())),
+    }
+}
+
+/// Wraps a value so that evaluating it gives back the same value.
+fn quote(expr: Expr) -> Expr {
+    match expr.kind {
+        ExprKind::Atom(Atom::Symbol(_))
+        | ExprKind::List(_)
+        | ExprKind::Func(_)
+        | ExprKind::SpecialForm(_)
+        | ExprKind::NativeFunc(_)
+        | ExprKind::Lambda(_) => {
+            ExprKind::List(vec![ExprKind::SpecialForm(list).into(), expr]).into()
+        }
+        ExprKind::Atom(_) | ExprKind::Regex(_) => expr,
+    }
+}
@@ -0,0 +1,496 @@
+use std::iter::Peekable;
+use std::str::Chars;
+
+use num_bigint::BigInt;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_bool, extract_string},
+    TokenGiver, TokenHoarder,
+};
+
+/// Parses a JSON document. Objects become lists starting with the symbol `object` followed by
+/// `(key value)` pairs, like `(object ("a" 1))`, so that they stay distinct from arrays, which
+/// become plain lists. `null` becomes unit. Integers stay exact, other numbers become floats.
+/// # Usage
+/// `(json-parse "{\"name\": \"spresso\", \"sizes\": [1, 2]}")`
+pub fn json_parse(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("json-parse", &args, 1, 1)?;
+    let text = extract_string(args[0].clone(), env)?;
+    JsonParser::new(&text).parse_document().map_err(|err| {
+        SpressoError::from(RuntimeError::from(format!(
+            "json-parse: {} at line {}, column {}",
+            err.message, err.line, err.column
+        )))
+        .maybe_with_tokens(args[0].get_tokens())
+    })
+}
+
+/// Converts a value into JSON. A list starting with the symbol `object` followed by `(key value)`
+/// pairs with string keys becomes an object, like `json-parse` gives, any other list becomes an
+/// array. Pretty-prints with an indent of two spaces when the second arg is true.
+/// # Usage
+/// `(json-stringify ('(object ("a" 1) ("b" (1 2)))) true)`
+pub fn json_stringify(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("json-stringify", &args, 1, 2)?;
+    let value = execute_single(args[0].clone(), env)?;
+    let pretty = match args.get(1) {
+        Some(arg) => extract_bool("json-stringify", arg.clone(), env)?,
+        None => false,
+    };
+
+    let mut res = String::new();
+    write_json(&value, pretty, 0, &mut res)
+        .map_err(|err| err.maybe_with_tokens(args[0].get_tokens()))?;
+    Ok(Expr::from(ExprKind::Atom(Atom::String(res))))
+}
+
+struct JsonError {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+/// Symbol at the head of a list which stands for an object.
+pub(crate) const OBJECT_TAG: &str = "object";
+
+/// Deepest nesting of arrays and objects the parser accepts, the same as serde_json. Deeper
+/// documents would overflow the stack of the recursive descent.
+const MAX_DEPTH: usize = 128;
+
+/// A recursive descent parser which keeps track of the line and column, for error messages.
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// Number of arrays and objects the parser is currently in.
+    depth: usize,
+}
+
+fn atom(atom: Atom) -> Expr {
+    Expr::from(ExprKind::Atom(atom))
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            line: 1,
+            column: 1,
+            depth: 0,
+        }
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, JsonError> {
+        Err(JsonError {
+            message: message.into(),
+            line: self.line,
+            column: self.column,
+        })
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some(&c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(&c) => self.error(format!("expected `{}`, found `{}`", expected, c)),
+            None => self.error(format!("expected `{}`, found the end of input", expected)),
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Expr, JsonError> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        match self.chars.peek() {
+            None => Ok(value),
+            Some(&c) => self.error(format!("unexpected `{}` after the value", c)),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Expr, JsonError> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('{') => self.parse_nested(Self::parse_object),
+            Some('[') => self.parse_nested(Self::parse_array),
+            Some('"') => Ok(atom(Atom::String(self.parse_string()?))),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some('t' | 'f' | 'n') => self.parse_literal(),
+            Some(&c) => self.error(format!("unexpected `{}`", c)),
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    /// Parses an array or an object, one level deeper than the current value.
+    fn parse_nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Expr, JsonError>,
+    ) -> Result<Expr, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return self.error(format!(
+                "arrays and objects are nested deeper than {} levels",
+                MAX_DEPTH
+            ));
+        }
+        self.depth += 1;
+        let res = parse(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn parse_object(&mut self) -> Result<Expr, JsonError> {
+        self.expect('{')?;
+        let mut pairs = vec![atom(Atom::Symbol(OBJECT_TAG.to_string()))];
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&'}') {
+            self.next();
+            return Ok(Expr::from(ExprKind::List(pairs)));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.chars.peek() != Some(&'"') {
+                return self.error("expected a string as the key");
+            }
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            pairs.push(Expr::from(ExprKind::List(vec![
+                atom(Atom::String(key)),
+                value,
+            ])));
+
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Expr::from(ExprKind::List(pairs))),
+                _ => return self.error("expected `,` or `}` after a value in an object"),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Expr, JsonError> {
+        self.expect('[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.chars.peek() == Some(&']') {
+            self.next();
+            return Ok(Expr::from(ExprKind::List(values)));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Expr::from(ExprKind::List(values))),
+                _ => return self.error("expected `,` or `]` after a value in an array"),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut res = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(res),
+                Some('\\') => match self.next() {
+                    Some('"') => res.push('"'),
+                    Some('\\') => res.push('\\'),
+                    Some('/') => res.push('/'),
+                    Some('b') => res.push('\u{8}'),
+                    Some('f') => res.push('\u{c}'),
+                    Some('n') => res.push('\n'),
+                    Some('r') => res.push('\r'),
+                    Some('t') => res.push('\t'),
+                    Some('u') => res.push(self.parse_unicode_escape()?),
+                    Some(c) => return self.error(format!("invalid escape `\\{}`", c)),
+                    None => return self.error("unterminated string"),
+                },
+                Some(c) if c < ' ' => {
+                    return self.error("control characters must be escaped in a string")
+                }
+                Some(c) => res.push(c),
+                None => return self.error("unterminated string"),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.next().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return self.error("expected 4 hex digits after `\\u`"),
+            }
+        }
+        Ok(code)
+    }
+
+    /// A `\uXXXX` escape, which may be the first half of a surrogate pair.
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.parse_hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return self.error("expected a low surrogate after a high surrogate");
+            }
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return self.error("expected a low surrogate after a high surrogate");
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        match char::from_u32(code) {
+            Some(c) => Ok(c),
+            None => self.error(format!("invalid unicode escape `\\u{:04x}`", code)),
+        }
+    }
+
+    fn take_digits(&mut self, text: &mut String) -> usize {
+        let mut count = 0;
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            text.push(c);
+            self.next();
+            count += 1;
+        }
+        count
+    }
+
+    fn parse_number(&mut self) -> Result<Expr, JsonError> {
+        let mut text = String::new();
+        if self.chars.peek() == Some(&'-') {
+            text.push('-');
+            self.next();
+        }
+
+        let int_start = text.len();
+        if self.take_digits(&mut text) == 0 {
+            return self.error("expected digits in a number");
+        }
+        if text[int_start..].starts_with('0') && text.len() - int_start > 1 {
+            return self.error("numbers cannot have leading zeros");
+        }
+
+        let mut is_float = false;
+        if self.chars.peek() == Some(&'.') {
+            is_float = true;
+            text.push('.');
+            self.next();
+            if self.take_digits(&mut text) == 0 {
+                return self.error("expected digits after the decimal point");
+            }
+        }
+        if matches!(self.chars.peek(), Some('e' | 'E')) {
+            is_float = true;
+            text.push('e');
+            self.next();
+            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                text.push(sign);
+                self.next();
+            }
+            if self.take_digits(&mut text) == 0 {
+                return self.error("expected digits in the exponent");
+            }
+        }
+
+        let num = if is_float {
+            match text.parse() {
+                Ok(float) => Number::Float(float),
+                Err(_) => return self.error(format!("invalid number `{}`", text)),
+            }
+        } else {
+            match text.parse::<BigInt>() {
+                Ok(int) => Number::from_bigint(int),
+                Err(_) => return self.error(format!("invalid number `{}`", text)),
+            }
+        };
+        Ok(atom(Atom::Number(num)))
+    }
+
+    fn parse_literal(&mut self) -> Result<Expr, JsonError> {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+            word.push(c);
+            self.next();
+        }
+
+        match word.as_str() {
+            "true" => Ok(atom(Atom::Bool(true))),
+            "false" => Ok(atom(Atom::Bool(false))),
+            "null" => Ok(atom(Atom::Unit)),
+            _ => self.error(format!("unexpected `{}`", word)),
+        }
+    }
+}
+
+/// The pairs of a list which looks like an object: every element is a `(key value)` pair with
+/// a string key. `None` for other lists.
+pub(crate) fn as_object(exprs: &[Expr]) -> Option<Vec<(&str, &Expr)>> {
+    if exprs.is_empty() {
+        return None;
+    }
+    exprs
+        .iter()
+        .map(|expr| match &expr.kind {
+            ExprKind::List(pair) if pair.len() == 2 => match &pair[0].kind {
+                ExprKind::Atom(Atom::String(key)) => Some((key.as_str(), &pair[1])),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The pairs of a list tagged as an object. `None` for untagged lists, which are arrays.
+pub(crate) fn object_pairs<'a>(
+    name: &str,
+    exprs: &'a [Expr],
+) -> Result<Option<Vec<(&'a str, &'a Expr)>>, SpressoError> {
+    let (head, pairs) = match exprs.split_first() {
+        Some(split) => split,
+        None => return Ok(None),
+    };
+    match &head.kind {
+        ExprKind::Atom(Atom::Symbol(tag)) if tag == OBJECT_TAG => {}
+        _ => return Ok(None),
+    }
+    if pairs.is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+    as_object(pairs).map(Some).ok_or_else(|| {
+        SpressoError::from(RuntimeError::from(format!(
+            "{}: an object can only contain (key value) pairs with string keys",
+            name
+        )))
+    })
+}
+
+fn write_json_string(string: &str, res: &mut String) {
+    res.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c < ' ' => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+}
+
+fn write_newline(pretty: bool, level: usize, res: &mut String) {
+    if pretty {
+        res.push('\n');
+        res.push_str(&"  ".repeat(level));
+    }
+}
+
+/// Writes the elements of an array or an object, with one element per line when pretty.
+fn write_elements<T>(
+    elements: &[T],
+    (open, close): (char, char),
+    pretty: bool,
+    level: usize,
+    res: &mut String,
+    mut write_element: impl FnMut(&T, &mut String) -> Result<(), SpressoError>,
+) -> Result<(), SpressoError> {
+    res.push(open);
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            res.push(',');
+        }
+        write_newline(pretty, level + 1, res);
+        write_element(element, res)?;
+    }
+    if !elements.is_empty() {
+        write_newline(pretty, level, res);
+    }
+    res.push(close);
+    Ok(())
+}
+
+fn write_json(
+    expr: &Expr,
+    pretty: bool,
+    level: usize,
+    res: &mut String,
+) -> Result<(), SpressoError> {
+    match &expr.kind {
+        ExprKind::Atom(Atom::Number(num)) => match num {
+            Number::Int(_) | Number::BigInt(_) => res.push_str(&num.to_string()),
+            _ => {
+                let float = num.to_f64();
+                if !float.is_finite() {
+                    return Err(SpressoError::from(RuntimeError::from(format!(
+                        "json-stringify: {} cannot be represented in JSON",
+                        num
+                    ))));
+                }
+                res.push_str(&format!("{:?}", float));
+            }
+        },
+        ExprKind::Atom(Atom::String(string)) | ExprKind::Atom(Atom::Symbol(string)) => {
+            write_json_string(string, res)
+        }
+        ExprKind::Atom(Atom::Bool(val)) => res.push_str(&val.to_string()),
+        ExprKind::Atom(Atom::Unit) => res.push_str("null"),
+        ExprKind::List(exprs) => match object_pairs("json-stringify", exprs)? {
+            Some(pairs) => write_elements(
+                &pairs,
+                ('{', '}'),
+                pretty,
+                level,
+                res,
+                |(key, value), res| {
+                    write_json_string(key, res);
+                    res.push_str(if pretty { ": " } else { ":" });
+                    write_json(value, pretty, level + 1, res)
+                },
+            )?,
+            None => write_elements(exprs, ('[', ']'), pretty, level, res, |value, res| {
+                write_json(value, pretty, level + 1, res)
+            })?,
+        },
+        kind => {
+            return Err(SpressoError::from(RuntimeError::from(format!(
+                "json-stringify: a {} cannot be represented in JSON",
+                kind.type_name()
+            ))))
+        }
+    }
+    Ok(())
+}
@@ -2,65 +2,50 @@ use crate::{
     ast::{Atom, Expr, ExprKind},
     env::Env,
     errors::{RuntimeError, SpressoError},
-    eval::execute_single,
+    eval::{check_arity, execute_single},
+    TokenGiver, TokenHoarder,
 };
 
-pub fn and(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(RuntimeError::from(
-            "`and` needs 2 arguments.",
-        )));
+/// Evaluates the expression and extracts a bool out of it.
+pub(crate) fn extract_bool(name: &str, expr: Expr, env: &mut Env) -> Result<bool, SpressoError> {
+    let tokens = expr.get_tokens();
+    let res = execute_single(expr, env)?;
+    match res.kind {
+        ExprKind::Atom(Atom::Bool(res)) => Ok(res),
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "`{}` needs bools, got: {}",
+            name, res
+        )))
+        .maybe_with_tokens(tokens)),
     }
+}
 
-    let lhs = execute_single(args[0].clone(), env)?;
-    let rhs = execute_single(args[1].clone(), env)?;
-    match lhs.kind {
-        ExprKind::Atom(Atom::Bool(lhs)) => match rhs.kind {
-            ExprKind::Atom(Atom::Bool(rhs)) => Ok(ExprKind::Atom(Atom::Bool(lhs && rhs)).into()),
-            _ => Err(SpressoError::from(RuntimeError::from(
-                "RHS needs to be bool",
-            ))),
-        },
-        _ => Err(SpressoError::from(RuntimeError::from(
-            "LHS needs to be bool",
-        ))),
+/// True if all of the args are true. Stops at the first false arg, without evaluating the rest.
+/// # Usage
+/// `(and (> x 0) (< x 10))`
+pub fn and(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    for arg in args {
+        if !extract_bool("and", arg, env)? {
+            return Ok(ExprKind::Atom(Atom::Bool(false)).into());
+        }
     }
+    Ok(ExprKind::Atom(Atom::Bool(true)).into())
 }
 
+/// True if any of the args is true. Stops at the first true arg, without evaluating the rest.
+/// # Usage
+/// `(or (< x 0) (> x 10))`
 pub fn or(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(RuntimeError::from(
-            "`or` needs 2 arguments.",
-        )));
-    }
-
-    let lhs = execute_single(args[0].clone(), env)?;
-    let rhs = execute_single(args[1].clone(), env)?;
-    match lhs.kind {
-        ExprKind::Atom(Atom::Bool(lhs)) => match rhs.kind {
-            ExprKind::Atom(Atom::Bool(rhs)) => Ok(ExprKind::Atom(Atom::Bool(lhs || rhs)).into()),
-            _ => Err(SpressoError::from(RuntimeError::from(
-                "RHS needs to be bool",
-            ))),
-        },
-        _ => Err(SpressoError::from(RuntimeError::from(
-            "LHS needs to be bool",
-        ))),
+    for arg in args {
+        if extract_bool("or", arg, env)? {
+            return Ok(ExprKind::Atom(Atom::Bool(true)).into());
+        }
     }
+    Ok(ExprKind::Atom(Atom::Bool(false)).into())
 }
 
 pub fn not(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 1 {
-        return Err(SpressoError::from(RuntimeError::from(
-            "`not` needs only 1 arguments.",
-        )));
-    }
-
-    let expr = execute_single(args[0].clone(), env)?;
-    match expr.kind {
-        ExprKind::Atom(Atom::Bool(arg)) => Ok(ExprKind::Atom(Atom::Bool(!arg)).into()),
-        _ => Err(SpressoError::from(RuntimeError::from(
-            "arg needs to be bool",
-        ))),
-    }
+    check_arity("not", &args, 1, 1)?;
+    let arg = extract_bool("not", args[0].clone(), env)?;
+    Ok(ExprKind::Atom(Atom::Bool(!arg)).into())
 }
@@ -0,0 +1,426 @@
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{NumericError, SpressoError},
+    eval::{
+        check_arity, check_min_arity, check_overflow, check_result_bits, extract_integer,
+        extract_num,
+    },
+    TokenGiver, TokenHoarder,
+};
+
+fn num_expr(num: Number) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Number(num)))
+}
+
+/// Evaluates the only arg of a `(<fn> num)` call.
+fn unary_arg(name: &str, args: &[Expr], env: &mut Env) -> Result<Number, SpressoError> {
+    check_arity(name, args, 1, 1)?;
+    extract_num(args[0].clone(), env)
+}
+
+fn domain_error(name: &str, expected: &str, num: &Number, expr: &Expr) -> SpressoError {
+    SpressoError::from(NumericError::from(format!(
+        "{}: expected {}, got: {}",
+        name, expected, num
+    )))
+    .maybe_with_tokens(expr.get_tokens())
+}
+
+fn division_by_zero(expr: &Expr) -> SpressoError {
+    SpressoError::from(NumericError::from("Division By Zero")).maybe_with_tokens(expr.get_tokens())
+}
+
+/// A function which always gives a float.
+fn float_fn(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    op: fn(f64) -> f64,
+) -> Result<Expr, SpressoError> {
+    let num = unary_arg(name, &args, env)?;
+    Ok(num_expr(Number::Float(op(num.to_f64()))))
+}
+
+/// Integer division and remainders. Exact numbers stay exact and a float on either side makes
+/// the result a float, like the arithmetic operators.
+fn division_op(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    int_op: fn(i64, i64) -> Option<i64>,
+    ratio_op: fn(BigRational, BigRational) -> BigRational,
+    float_op: fn(f64, f64) -> f64,
+) -> Result<Expr, SpressoError> {
+    check_arity(name, &args, 2, 2)?;
+    let lhs = extract_num(args[0].clone(), env)?;
+    let rhs = extract_num(args[1].clone(), env)?;
+    if rhs == Number::Int(0) {
+        return Err(division_by_zero(&args[1]));
+    }
+
+    let res = lhs.apply(rhs, int_op, ratio_op, float_op);
+    Ok(num_expr(
+        check_overflow(res, env).map_err(|err| err.maybe_with_tokens(args.get_tokens()))?,
+    ))
+}
+
+/// Remainder with the sign of the divisor.
+/// # Usage
+/// `(mod 7 3)`
+pub fn modulo(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    division_op(
+        "mod",
+        args,
+        env,
+        |x, y| {
+            let rem = x.checked_rem(y)?;
+            Some(if rem != 0 && (rem < 0) != (y < 0) {
+                rem + y
+            } else {
+                rem
+            })
+        },
+        |x, y| {
+            let quot = (&x / &y).floor();
+            x - y * quot
+        },
+        |x, y| {
+            let rem = x % y;
+            if rem != 0.0 && (rem < 0.0) != (y < 0.0) {
+                rem + y
+            } else {
+                rem
+            }
+        },
+    )
+}
+
+/// Remainder with the sign of the dividend.
+/// # Usage
+/// `(rem 7 3)`
+pub fn rem(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    division_op(
+        "rem",
+        args,
+        env,
+        i64::checked_rem,
+        |x, y| {
+            let quot = (&x / &y).trunc();
+            x - y * quot
+        },
+        |x, y| x % y,
+    )
+}
+
+/// Quotient truncated towards zero.
+/// # Usage
+/// `(quot 7 2)`
+pub fn quot(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    division_op(
+        "quot",
+        args,
+        env,
+        i64::checked_div,
+        |x, y| (x / y).trunc(),
+        |x, y| (x / y).trunc(),
+    )
+}
+
+/// Raises a number to a power. An exact number raised to an integer stays exact, anything else
+/// gives a float.
+/// # Usage
+/// `(expt 2 10)`
+pub fn expt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("expt", &args, 2, 2)?;
+    let base = extract_num(args[0].clone(), env)?;
+    let power = extract_num(args[1].clone(), env)?;
+
+    let res = match (base.to_ratio(), power.to_bigint()) {
+        (Some(exact_base), Some(power)) => {
+            let power = match power.to_i32() {
+                Some(power) => power,
+                None => {
+                    return Err(SpressoError::from(NumericError::from(format!(
+                        "expt: exponent {} is too large",
+                        power
+                    )))
+                    .maybe_with_tokens(args[1].get_tokens()))
+                }
+            };
+            if exact_base.is_zero() && power < 0 {
+                return Err(division_by_zero(&args[0]));
+            }
+
+            // the result has at least this many bits, which is exact for powers of two
+            let base_bits = exact_base.numer().bits().max(exact_base.denom().bits());
+            let bits = (base_bits - 1).saturating_mul(power.unsigned_abs().into()) + 1;
+            check_result_bits("expt", bits, exact_base.is_integer() && power >= 0, env)
+                .map_err(|err| err.maybe_with_tokens(args.get_tokens()))?;
+
+            match base {
+                Number::Int(base) if power >= 0 => match base.checked_pow(power as u32) {
+                    Some(res) => Number::Int(res),
+                    None => Number::from_bigint(BigInt::from(base).pow(power as u32)),
+                },
+                _ => Number::from_ratio(exact_base.pow(power)),
+            }
+        }
+        _ => Number::Float(base.to_f64().powf(power.to_f64())),
+    };
+
+    Ok(num_expr(
+        check_overflow(res, env).map_err(|err| err.maybe_with_tokens(args.get_tokens()))?,
+    ))
+}
+
+/// Square root. Exact for integers which are perfect squares, a float otherwise.
+/// # Usage
+/// `(sqrt 16)`
+pub fn sqrt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let num = unary_arg("sqrt", &args, env)?;
+    if num < Number::Int(0) {
+        return Err(domain_error(
+            "sqrt",
+            "a non-negative number",
+            &num,
+            &args[0],
+        ));
+    }
+
+    if let Some(int) = num.to_bigint() {
+        let root = int.sqrt();
+        if &root * &root == int {
+            return Ok(num_expr(Number::from_bigint(root)));
+        }
+    }
+    Ok(num_expr(Number::Float(num.to_f64().sqrt())))
+}
+
+/// e raised to the number.
+/// # Usage
+/// `(exp 1)`
+pub fn exp(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    float_fn("exp", args, env, f64::exp)
+}
+
+/// Natural logarithm, or the logarithm to the given base.
+/// # Usage
+/// `(log 100 10)`
+pub fn log(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("log", &args, 1, 2)?;
+    let mut logs = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        let num = extract_num(arg.clone(), env)?;
+        // NaN is not comparable, so it is rejected too
+        if num.partial_cmp(&Number::Int(0)) != Some(Ordering::Greater) {
+            return Err(domain_error("log", "a positive number", &num, arg));
+        }
+        logs.push(num.to_f64().ln());
+    }
+
+    Ok(num_expr(Number::Float(match logs[..] {
+        [num, base] => num / base,
+        _ => logs[0],
+    })))
+}
+
+/// # Usage
+/// `(sin (/ pi 2))`
+pub fn sin(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    float_fn("sin", args, env, f64::sin)
+}
+
+/// # Usage
+/// `(cos pi)`
+pub fn cos(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    float_fn("cos", args, env, f64::cos)
+}
+
+/// # Usage
+/// `(tan (/ pi 4))`
+pub fn tan(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    float_fn("tan", args, env, f64::tan)
+}
+
+/// Inverse of `sin` and `cos`, defined for numbers between -1 and 1.
+fn inverse_trig_fn(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    op: fn(f64) -> f64,
+) -> Result<Expr, SpressoError> {
+    let num = unary_arg(name, &args, env)?;
+    let float = num.to_f64();
+    if !(-1.0..=1.0).contains(&float) {
+        return Err(domain_error(
+            name,
+            "a number between -1 and 1",
+            &num,
+            &args[0],
+        ));
+    }
+    Ok(num_expr(Number::Float(op(float))))
+}
+
+/// # Usage
+/// `(asin 1)`
+pub fn asin(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    inverse_trig_fn("asin", args, env, f64::asin)
+}
+
+/// # Usage
+/// `(acos 1)`
+pub fn acos(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    inverse_trig_fn("acos", args, env, f64::acos)
+}
+
+/// Arc tangent of a number, or of `y / x` (using the signs of both to find the quadrant) when
+/// given two numbers.
+/// # Usage
+/// `(atan 1)` or `(atan y x)`
+pub fn atan(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("atan", &args, 1, 2)?;
+    let y = extract_num(args[0].clone(), env)?.to_f64();
+    let res = match args.get(1) {
+        Some(x) => y.atan2(extract_num(x.clone(), env)?.to_f64()),
+        None => y.atan(),
+    };
+    Ok(num_expr(Number::Float(res)))
+}
+
+/// Rounds ratios to an integer and floats to a float without a fractional part. Integers are
+/// already round.
+fn rounding_fn(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    ratio_op: fn(&BigRational) -> BigRational,
+    float_op: fn(f64) -> f64,
+) -> Result<Expr, SpressoError> {
+    let num = unary_arg(name, &args, env)?;
+    let res = match num {
+        Number::Ratio(num) => Number::from_ratio(ratio_op(&num)),
+        Number::Float(num) => Number::Float(float_op(num)),
+        num => num,
+    };
+    Ok(num_expr(
+        check_overflow(res, env).map_err(|err| err.maybe_with_tokens(args.get_tokens()))?,
+    ))
+}
+
+/// Largest integer not greater than the number.
+/// # Usage
+/// `(floor 7/2)`
+pub fn floor(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    rounding_fn("floor", args, env, BigRational::floor, f64::floor)
+}
+
+/// Smallest integer not less than the number.
+/// # Usage
+/// `(ceil 7/2)`
+pub fn ceil(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    rounding_fn("ceil", args, env, BigRational::ceil, f64::ceil)
+}
+
+/// Nearest integer, rounding half-way cases away from zero.
+/// # Usage
+/// `(round 2.5)`
+pub fn round(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    rounding_fn("round", args, env, BigRational::round, f64::round)
+}
+
+/// Integer part of the number, rounding towards zero.
+/// # Usage
+/// `(truncate 7/2)`
+pub fn truncate(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    rounding_fn("truncate", args, env, BigRational::trunc, f64::trunc)
+}
+
+/// # Usage
+/// `(abs (- 2 5))`
+pub fn abs(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let num = unary_arg("abs", &args, env)?;
+    let res = match num {
+        Number::Int(num) => match num.checked_abs() {
+            Some(res) => Number::Int(res),
+            None => Number::from_bigint(BigInt::from(num).abs()),
+        },
+        Number::BigInt(num) => Number::BigInt(num.abs()),
+        Number::Ratio(num) => Number::Ratio(num.abs()),
+        Number::Float(num) => Number::Float(num.abs()),
+    };
+    Ok(num_expr(
+        check_overflow(res, env).map_err(|err| err.maybe_with_tokens(args.get_tokens()))?,
+    ))
+}
+
+/// Picks one of the numbers. `pick_new` decides if the new number replaces the current one.
+fn pick_num(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    pick_new: fn(&Number, &Number) -> bool,
+) -> Result<Expr, SpressoError> {
+    check_min_arity(name, &args, 1)?;
+    let mut picked = extract_num(args[0].clone(), env)?;
+    for arg in args.into_iter().skip(1) {
+        let num = extract_num(arg, env)?;
+        if pick_new(&num, &picked) {
+            picked = num;
+        }
+    }
+    Ok(num_expr(picked))
+}
+
+/// # Usage
+/// `(min 3 1 2)`
+pub fn min(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    pick_num("min", args, env, |new, picked| new < picked)
+}
+
+/// # Usage
+/// `(max 3 1 2)`
+pub fn max(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    pick_num("max", args, env, |new, picked| new > picked)
+}
+
+/// Folds integer args with `op`, starting from `init`.
+fn integer_fold(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    init: BigInt,
+    op: fn(&BigInt, &BigInt) -> BigInt,
+) -> Result<Expr, SpressoError> {
+    let tokens = args.get_tokens();
+    let mut res = init;
+    for arg in args {
+        res = op(&res, &extract_integer(name, arg, env)?);
+    }
+    Ok(num_expr(
+        check_overflow(Number::from_bigint(res), env)
+            .map_err(|err| err.maybe_with_tokens(tokens))?,
+    ))
+}
+
+/// Greatest common divisor of any number of integers. `(gcd)` is 0.
+/// # Usage
+/// `(gcd 12 18)`
+pub fn gcd(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    integer_fold("gcd", args, env, BigInt::zero(), BigInt::gcd)
+}
+
+/// Least common multiple of any number of integers. `(lcm)` is 1.
+/// # Usage
+/// `(lcm 4 6)`
+pub fn lcm(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    integer_fold("lcm", args, env, BigInt::from(1), BigInt::lcm)
+}
@@ -1,21 +1,43 @@
+mod bitwise;
 mod conditional;
+mod csv;
+mod format;
+mod fs;
 mod functions;
+mod json;
 mod lists;
 mod logical;
 mod loops;
+mod math;
 mod number;
+mod process;
+mod random;
+mod regexp;
 mod relational;
+mod strings;
+mod symbols;
+mod time;
 mod types;
 
-use std::io;
-
+pub use bitwise::*;
 pub use conditional::*;
+pub use csv::*;
+pub use format::*;
+pub use fs::*;
 pub use functions::*;
+pub use json::*;
 pub use lists::*;
 pub use logical::*;
 pub use loops::*;
+pub use math::*;
 pub use number::*;
+pub use process::*;
+pub use random::*;
+pub use regexp::*;
 pub use relational::*;
+pub use strings::*;
+pub use symbols::*;
+pub use time::*;
 pub use types::*;
 
 use crate::{
@@ -28,7 +50,8 @@ use crate::{
 pub fn execute(exprs: &mut Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     let first_arg = exprs[0].clone();
     match first_arg.kind {
-        ExprKind::Func(func) => func(exprs[1..].to_vec(), env),
+        ExprKind::Func(func) | ExprKind::SpecialForm(func) => func(exprs[1..].to_vec(), env),
+        ExprKind::NativeFunc(func) => func(exprs[1..].to_vec(), env),
         ExprKind::List(mut list) => {
             let res = execute(&mut list, env)?;
             let mut evaluated = exprs[1..].to_vec();
@@ -43,12 +66,6 @@ pub fn execute(exprs: &mut Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoErro
             exprs[0] = value?;
             execute(exprs, env)
         }
-        ExprKind::Atom(Atom::String(_)) => {
-            Ok(first_arg)
-        }
-        ExprKind::Atom(Atom::Number(_)) => {
-            Ok(first_arg)
-        }
         ExprKind::Lambda(lambda) => execute_lambda(lambda, exprs[1..].to_vec(), env),
         _ => Err(SpressoError::from(RuntimeError::from(format!(
             "this is not something I can execute: {}",
@@ -60,13 +77,14 @@ pub fn execute(exprs: &mut Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoErro
 
 pub fn execute_single(expr: Expr, env: &mut Env) -> Result<Expr, SpressoError> {
     let res = match expr.kind {
-        ExprKind::Func(func) => func(vec![], env),
+        ExprKind::Func(func) | ExprKind::SpecialForm(func) => func(vec![], env),
+        ExprKind::NativeFunc(func) => func(vec![], env),
         ExprKind::Atom(Atom::Symbol(ref symbol)) => env
             .get_symbol(symbol.as_str())
             .maybe_with_tokens(expr.get_tokens()),
         ExprKind::List(mut exprs) => execute(&mut exprs, env),
         ExprKind::Lambda(lambda) => execute_lambda(lambda, vec![], env),
-        ExprKind::Atom(_) => Ok(expr),
+        ExprKind::Atom(_) | ExprKind::Regex(_) => Ok(expr),
     };
 
     env.cleanup();
@@ -91,17 +109,27 @@ pub fn define(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
 pub fn print(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     for arg in args {
         let result = execute_single(arg, env)?;
-        println!("{}", result);
+        write_output(env, &format!("{}\n", result))?;
     }
     Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
 }
 
+/// Writes to the env's output stream. Flushes it too, so that text without a newline, like a
+/// prompt, shows up immediately.
+pub(crate) fn write_output(env: &mut Env, text: &str) -> Result<(), SpressoError> {
+    let output = env.output();
+    output
+        .write_all(text.as_bytes())
+        .and_then(|_| output.flush())
+        .map_err(|err| SpressoError::from(RuntimeError::from(format!("{}", err))))
+}
+
 pub fn input(_args: Vec<Expr>, _env: &mut Env) -> Result<Expr, SpressoError> {
-    if _args.len() > 0 {
+    if !_args.is_empty() {
         print(_args, _env)?;
     }
     let mut buffer = String::new();
-    if let Err(err) = io::stdin().read_line(&mut buffer) {
+    if let Err(err) = _env.read_line(&mut buffer) {
         return Err(SpressoError::from(RuntimeError::from(format!("{}", err))));
     }
     buffer = buffer.trim().to_string();
@@ -117,3 +145,44 @@ pub fn list(args: Vec<Expr>, _: &mut Env) -> Result<Expr, SpressoError> {
     }
     Ok(args[0].clone())
 }
+
+/// Returns an error if the number of args is not between `min` and `max` (both inclusive).
+pub(crate) fn check_arity(
+    name: &str,
+    args: &[Expr],
+    min: usize,
+    max: usize,
+) -> Result<(), SpressoError> {
+    if (min..=max).contains(&args.len()) {
+        return Ok(());
+    }
+
+    let expected = if min == max {
+        format!("{}", min)
+    } else {
+        format!("{} to {}", min, max)
+    };
+    Err(SpressoError::from(RuntimeError::from(format!(
+        "`{}` needs {} arguments, got {}",
+        name,
+        expected,
+        args.len()
+    )))
+    .maybe_with_tokens(args.get_tokens()))
+}
+
+/// Returns an error if there are less than `min` args.
+pub(crate) fn check_min_arity(name: &str, args: &[Expr], min: usize) -> Result<(), SpressoError> {
+    if args.len() >= min {
+        return Ok(());
+    }
+
+    Err(SpressoError::from(RuntimeError::from(format!(
+        "`{}` needs at least {} argument{}, got {}",
+        name,
+        min,
+        if min == 1 { "" } else { "s" },
+        args.len()
+    )))
+    .maybe_with_tokens(args.get_tokens()))
+}
@@ -1,8 +1,11 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
 use crate::{
     ast::{Atom, Expr, ExprKind, Number},
     env::Env,
     errors::{NumericError, RuntimeError, SpressoError},
-    eval::execute,
+    eval::{check_arity, check_min_arity, execute},
     TokenGiver, TokenHoarder,
 };
 
@@ -16,28 +19,95 @@ fn number_op(
     Ok(Expr::from(ExprKind::Atom(Atom::Number(
         args.into_iter()
             .try_fold::<_, _, Result<Number, SpressoError>>(init, |x, y| {
+                // errors mark the argument which caused them
+                let arg_tokens = y.get_tokens();
                 let num = extract_num(y, env)?;
                 op(x, num)
+                    .and_then(|res| check_overflow(res, env))
+                    .map_err(|err| err.maybe_with_tokens(arg_tokens))
             })?,
     )))
     .maybe_with_tokens(tokens))
 }
 
+/// In strict arithmetic mode, an integer result which does not fit in an i64 is an error.
+pub(crate) fn check_overflow(num: Number, env: &Env) -> Result<Number, SpressoError> {
+    match num {
+        Number::BigInt(_) if env.is_strict_arithmetic() => {
+            Err(SpressoError::from(NumericError::from("Integer overflow")))
+        }
+        num => Ok(num),
+    }
+}
+
+/// Largest integer, in bits, that builtins like `expt` will compute. Larger results take so
+/// long to build that they are an error instead.
+pub(crate) const MAX_INTEGER_BITS: u64 = 1 << 22;
+
+/// Checks how many bits a result will have before it is computed, so that results which are
+/// too large, or which would overflow in strict arithmetic mode, are rejected without building
+/// them first. `bits` must not be more than the result actually has.
+pub(crate) fn check_result_bits(
+    name: &str,
+    bits: u64,
+    is_integer: bool,
+    env: &Env,
+) -> Result<(), SpressoError> {
+    if is_integer && bits > 64 && env.is_strict_arithmetic() {
+        return Err(SpressoError::from(NumericError::from("Integer overflow")));
+    }
+    if bits > MAX_INTEGER_BITS {
+        return Err(SpressoError::from(NumericError::from(format!(
+            "{}: the result would have more than {} bits",
+            name, MAX_INTEGER_BITS
+        ))));
+    }
+    Ok(())
+}
+
+/// Extracts the first arg of `-` and `/`, which the rest are applied to.
+fn first_num(name: &str, args: &mut Vec<Expr>, env: &mut Env) -> Result<Number, SpressoError> {
+    check_min_arity(name, args, 1)?;
+    extract_num(args.remove(0), env)
+}
+
+
+/// Evaluates the expression and extracts an integer out of it. Ratios and floats are rejected.
+pub fn extract_integer(name: &str, expr: Expr, env: &mut Env) -> Result<BigInt, SpressoError> {
+    let tokens = expr.get_tokens();
+    let num = extract_num(expr, env)?;
+    num.to_bigint().ok_or_else(|| {
+        SpressoError::from(NumericError::from(format!(
+            "{}: expected an integer, got: {}",
+            name, num
+        )))
+        .maybe_with_tokens(tokens)
+    })
+}
+
+/// Implicitly converts a string into a number, unless the env does not allow it.
+pub(crate) fn coerce_string(string: &str, env: &Env) -> Result<Number, SpressoError> {
+    if !env.is_string_coercion_enabled() {
+        return Err(SpressoError::from(NumericError::from(format!(
+            "Expected a number, got a string: \"{}\" (implicit conversion of strings to numbers is disabled)",
+            string
+        ))));
+    }
+
+    match Number::parse(string) {
+        Some(num) => Ok(num),
+        None => Err(SpressoError::from(NumericError {
+            err: "Tried to extract number from string but failed".to_string(),
+        })),
+    }
+}
 
 pub fn extract_num(expr: Expr, env: &mut Env) -> Result<Number, SpressoError> {
     match expr.kind {
         ExprKind::Atom(Atom::Number(number)) => Ok(number),
-        ExprKind::Atom(Atom::String(str)) => {
-            if let Ok(num) = str.parse::<i64>() {
-                Ok(Number::Int(num))
-            } else if let Ok(num) = str.parse::<f64>() {
-                Ok(Number::Float(num))
-            } else {
-                Err(SpressoError::from(NumericError {
-                        err: "Tried to extract number from string but failed".to_string(),
-                }))
-            }
-        },
+        ExprKind::Atom(Atom::String(ref str)) => {
+            coerce_string(str, env).map_err(|err| err.maybe_with_tokens(expr.get_tokens()))
+        }
         ExprKind::Atom(Atom::Symbol(ref symbol)) => {
             if env.contains_key(symbol.as_str()) {
                 let sym = env[symbol.as_str()].clone();
@@ -60,16 +130,8 @@ pub fn extract_num(expr: Expr, env: &mut Env) -> Result<Number, SpressoError> {
             let res = execute(&mut exprs, env)?;
             match res.kind {
                 ExprKind::Atom(Atom::Number(num)) => Ok(num),
-                ExprKind::Atom(Atom::String(str)) => {
-                    if let Ok(num) = str.parse::<i64>() {
-                        Ok(Number::Int(num))
-                    } else if let Ok(num) = str.parse::<f64>() {
-                        Ok(Number::Float(num))
-                    } else {
-                        Err(SpressoError::from(NumericError {
-                                err: "Tried to extract number from string but failed".to_string(),
-                        }))
-                    }
+                ExprKind::Atom(Atom::String(ref str)) => {
+                    coerce_string(str, env).map_err(|err| err.maybe_with_tokens(res.get_tokens()))
                 }
                 _ => Err(SpressoError::from(NumericError::from(format!(
                     "trying to perform arithmetic on non-number: {}",
@@ -96,16 +158,76 @@ pub fn mul(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
 
 pub fn sub(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     let mut args = args;
-    let start = extract_num(args.remove(0), env)?;
+    let start = first_num("-", &mut args, env)?;
     number_op(args, env, start, |x, y| x - y)
 }
 
 pub fn div(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     let mut args = args;
-    let start = extract_num(args.remove(0), env)?;
-    // TODO: find a better way instead of cloning
-    match number_op(args.clone(), env, start, |x, y| x / y) {
-        Err(err) => Err(err.maybe_with_tokens(args.get_tokens())),
-        Ok(res) => Ok(res),
+    let start = first_num("/", &mut args, env)?;
+    number_op(args, env, start, |x, y| x / y)
+}
+
+fn num_expr(num: Number) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Number(num)))
+}
+
+/// Evaluates the only arg of a `(<fn> num)` call and extracts its exact value.
+fn extract_exact(name: &str, args: &[Expr], env: &mut Env) -> Result<BigRational, SpressoError> {
+    check_arity(name, args, 1, 1)?;
+    let num = extract_num(args[0].clone(), env)?;
+    num.to_ratio().ok_or_else(|| {
+        SpressoError::from(NumericError::from(format!(
+            "{}: expected an exact number, got: {}",
+            name, num
+        )))
+        .maybe_with_tokens(args[0].get_tokens())
+    })
+}
+
+/// Numerator of a ratio in its lowest terms. An integer is its own numerator.
+/// # Usage
+/// `(numerator (/ 6 4))`
+pub fn numerator(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let num = extract_exact("numerator", &args, env)?;
+    Ok(num_expr(Number::from_bigint(num.numer().clone())))
+}
+
+/// Denominator of a ratio in its lowest terms. The denominator of an integer is 1.
+/// # Usage
+/// `(denominator (/ 6 4))`
+pub fn denominator(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let num = extract_exact("denominator", &args, env)?;
+    Ok(num_expr(Number::from_bigint(num.denom().clone())))
+}
+
+/// Converts a number into a float.
+/// # Usage
+/// `(exact->inexact 7/2)`
+pub fn exact_to_inexact(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("exact->inexact", &args, 1, 1)?;
+    let num = extract_num(args[0].clone(), env)?;
+    Ok(num_expr(Number::Float(num.to_f64())))
+}
+
+/// Converts a float into the exact integer or ratio it represents.
+/// # Usage
+/// `(inexact->exact 0.5)`
+pub fn inexact_to_exact(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("inexact->exact", &args, 1, 1)?;
+    let num = extract_num(args[0].clone(), env)?;
+    match num {
+        Number::Float(float) => match BigRational::from_float(float) {
+            Some(exact) => Ok(num_expr(
+                check_overflow(Number::from_ratio(exact), env)
+                    .map_err(|err| err.maybe_with_tokens(args.get_tokens()))?,
+            )),
+            None => Err(SpressoError::from(NumericError::from(format!(
+                "inexact->exact: {} has no exact value",
+                float
+            )))
+            .maybe_with_tokens(args[0].get_tokens())),
+        },
+        exact => Ok(num_expr(exact)),
     }
 }
@@ -0,0 +1,125 @@
+use std::env as std_env;
+use std::process::Command;
+
+use num_traits::ToPrimitive;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{ExitRequest, IoError, RuntimeError, SpressoError},
+    eval::{check_arity, check_min_arity, extract_integer, extract_string},
+    TokenGiver, TokenHoarder,
+};
+
+fn string_expr(string: String) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::String(string)))
+}
+
+fn unit_expr() -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Unit))
+}
+
+/// Value of an environment variable, or unit if it is not set.
+/// # Usage
+/// `(getenv "HOME")`
+pub fn getenv(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("getenv", &args, 1, 1)?;
+    let name = extract_string(args[0].clone(), env)?;
+    Ok(match std_env::var(&name) {
+        Ok(value) => string_expr(value),
+        Err(_) => unit_expr(),
+    })
+}
+
+/// Sets an environment variable, for this process and the processes it runs.
+///
+/// The environment is shared by the whole process, and changing it while another thread reads
+/// it is not thread-safe. So it is not safe to use in a host which runs other threads, even
+/// ones that only read the environment or start processes.
+/// # Usage
+/// `(setenv "LANG" "C")`
+pub fn setenv(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("setenv", &args, 2, 2)?;
+    let name = extract_string(args[0].clone(), env)?;
+    let value = extract_string(args[1].clone(), env)?;
+    if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+        return Err(SpressoError::from(RuntimeError::from(format!(
+            "setenv: invalid environment variable `{}`",
+            name
+        )))
+        .maybe_with_tokens(args.get_tokens()));
+    }
+    std_env::set_var(name, value);
+    Ok(unit_expr())
+}
+
+/// Stops the program. The status code is 0 when not given, otherwise it must be between 0 and
+/// 255, since that is all the OS keeps of it. When running a file, it becomes the status code
+/// of the interpreter.
+/// # Usage
+/// `(exit 1)`
+pub fn exit(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("exit", &args, 0, 1)?;
+    let code = match args.first() {
+        Some(arg) => {
+            let code = extract_integer("exit", arg.clone(), env)?;
+            match code.to_u8() {
+                Some(code) => code.into(),
+                None => {
+                    return Err(SpressoError::from(RuntimeError::from(format!(
+                        "exit: status code {} is not between 0 and 255",
+                        code
+                    )))
+                    .maybe_with_tokens(arg.get_tokens()))
+                }
+            }
+        }
+        None => 0,
+    };
+    Err(SpressoError::from(ExitRequest::from(code)))
+}
+
+/// Runs a program with the given args and waits for it to finish. Returns a list of
+/// `(key value)` pairs with its `stdout`, `stderr` and `status`. The status is unit when the
+/// program was stopped by a signal. The program is not run through a shell, use
+/// `(run-process "sh" "-c" "...")` for that.
+/// # Usage
+/// `(run-process "git" "status" "--short")`
+pub fn run_process(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_min_arity("run-process", &args, 1)?;
+    let program = extract_string(args[0].clone(), env)?;
+    let program_args = args[1..]
+        .iter()
+        .map(|arg| extract_string(arg.clone(), env))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output = Command::new(&program)
+        .args(&program_args)
+        .output()
+        .map_err(|err| {
+            SpressoError::from(IoError::new(
+                format!("run-process: could not run `{}`", program),
+                &err,
+            ))
+            .maybe_with_tokens(args.get_tokens())
+        })?;
+
+    let status = match output.status.code() {
+        Some(code) => Expr::from(ExprKind::Atom(Atom::Number(Number::Int(code.into())))),
+        None => unit_expr(),
+    };
+    let pair = |key: &str, value: Expr| {
+        Expr::from(ExprKind::List(vec![string_expr(key.to_string()), value]))
+    };
+    Ok(Expr::from(ExprKind::List(vec![
+        pair(
+            "stdout",
+            string_expr(String::from_utf8_lossy(&output.stdout).into_owned()),
+        ),
+        pair(
+            "stderr",
+            string_expr(String::from_utf8_lossy(&output.stderr).into_owned()),
+        ),
+        pair("status", status),
+    ])))
+}
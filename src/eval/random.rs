@@ -0,0 +1,136 @@
+use num_traits::ToPrimitive;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{NumericError, RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_integer, extract_num},
+    TokenGiver, TokenHoarder,
+};
+
+/// Evaluates the expression and extracts an integer which fits in an i64 out of it.
+fn extract_i64(name: &str, expr: Expr, env: &mut Env) -> Result<i64, SpressoError> {
+    let tokens = expr.get_tokens();
+    let num = extract_integer(name, expr, env)?;
+    num.to_i64().ok_or_else(|| {
+        SpressoError::from(NumericError::from(format!(
+            "{}: {} does not fit in 64 bits",
+            name, num
+        )))
+        .maybe_with_tokens(tokens)
+    })
+}
+
+/// Evaluates the expression and extracts a list out of it.
+fn extract_list(name: &str, expr: Expr, env: &mut Env) -> Result<Vec<Expr>, SpressoError> {
+    let tokens = expr.get_tokens();
+    let res = execute_single(expr, env)?;
+    if let ExprKind::List(list) = res.kind {
+        Ok(list)
+    } else {
+        Err(SpressoError::from(RuntimeError::from(format!(
+            "{}: expected list as input got something else",
+            name
+        )))
+        .maybe_with_tokens(tokens))
+    }
+}
+
+fn empty_range(name: &str, args: &[Expr]) -> SpressoError {
+    SpressoError::from(RuntimeError::from(format!(
+        "{}: the range is empty, the upper bound must be greater than the lower bound",
+        name
+    )))
+    .maybe_with_tokens(args.get_tokens())
+}
+
+/// Seeds the random number generator of this env, so that the random builtins give the same
+/// results every time the script is run.
+/// # Usage
+/// `(random-seed 42)`
+pub fn random_seed(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("random-seed", &args, 1, 1)?;
+    let seed = extract_i64("random-seed", args[0].clone(), env)?;
+    env.set_random_seed(seed as u64);
+    Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
+}
+
+/// A random integer from `low` (inclusive, defaults to 0) till `high` (exclusive).
+/// # Usage
+/// `(random-int 10)` or `(random-int 1 7)`
+pub fn random_int(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("random-int", &args, 1, 2)?;
+    let (low, high) = match args.len() {
+        1 => (0, extract_i64("random-int", args[0].clone(), env)?),
+        _ => (
+            extract_i64("random-int", args[0].clone(), env)?,
+            extract_i64("random-int", args[1].clone(), env)?,
+        ),
+    };
+    if high <= low {
+        return Err(empty_range("random-int", &args));
+    }
+
+    // the difference of two i64s always fits in a u64
+    let offset = env.rng().below(high.wrapping_sub(low) as u64);
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(Number::Int(
+        low.wrapping_add(offset as i64),
+    )))))
+}
+
+/// A random float from `low` (inclusive, defaults to 0) till `high` (exclusive, defaults to 1).
+/// # Usage
+/// `(random-float)` or `(random-float 1.5 2.5)`
+pub fn random_float(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("random-float", &args, 0, 2)?;
+    let (low, high) = match args.len() {
+        0 => (0.0, 1.0),
+        1 => (0.0, extract_num(args[0].clone(), env)?.to_f64()),
+        _ => (
+            extract_num(args[0].clone(), env)?.to_f64(),
+            extract_num(args[1].clone(), env)?.to_f64(),
+        ),
+    };
+    if !low.is_finite() || !high.is_finite() || high <= low {
+        return Err(empty_range("random-float", &args));
+    }
+
+    // `high - low` overflows for bounds far apart, like -1e308 and 1e308, so the bounds are
+    // weighted instead. Rounding can still give `high`, which is excluded.
+    let r = env.rng().next_f64();
+    let res = (low * (1.0 - r) + high * r).max(low);
+    let res = if res < high { res } else { high.next_down() };
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(Number::Float(res)))))
+}
+
+/// A new list with the elements of the list in a random order.
+/// # Usage
+/// `(shuffle ('(1 2 3 4)))`
+pub fn shuffle(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("shuffle", &args, 1, 1)?;
+    let mut list = extract_list("shuffle", args[0].clone(), env)?;
+
+    // Fisher-Yates shuffle
+    for i in (1..list.len()).rev() {
+        let j = env.rng().below(i as u64 + 1) as usize;
+        list.swap(i, j);
+    }
+    Ok(Expr::from(ExprKind::List(list)))
+}
+
+/// A random element of a non-empty list.
+/// # Usage
+/// `(choice ('("rock" "paper" "scissors")))`
+pub fn choice(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("choice", &args, 1, 1)?;
+    let mut list = extract_list("choice", args[0].clone(), env)?;
+    if list.is_empty() {
+        return Err(
+            SpressoError::from(RuntimeError::from("choice: the list is empty"))
+                .maybe_with_tokens(args[0].get_tokens()),
+        );
+    }
+
+    let index = env.rng().below(list.len() as u64) as usize;
+    Ok(list.swap_remove(index))
+}
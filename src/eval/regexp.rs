@@ -0,0 +1,142 @@
+use regex::Regex;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_string},
+    Token, TokenGiver, TokenHoarder,
+};
+
+fn compile(pattern: &str, tokens: Option<Vec<Token>>) -> Result<Regex, SpressoError> {
+    Regex::new(pattern).map_err(|err| {
+        SpressoError::from(RuntimeError::from(format!("Invalid regex: {}", err)))
+            .maybe_with_tokens(tokens)
+    })
+}
+
+/// Evaluates the expression and extracts a regex out of it.
+/// A string is compiled into a regex, so that a pattern can be used directly.
+fn extract_regex(expr: Expr, env: &mut Env) -> Result<Regex, SpressoError> {
+    let tokens = expr.get_tokens();
+    let res = execute_single(expr, env)?;
+    match res.kind {
+        ExprKind::Regex(regex) => Ok(regex),
+        ExprKind::Atom(Atom::String(pattern)) => compile(&pattern, tokens),
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "Expected a regex or a pattern string, got: {}",
+            res
+        )))
+        .maybe_with_tokens(tokens)),
+    }
+}
+
+/// Evaluates the args of a `(<fn> regex string ...)` call.
+fn regex_and_string(
+    name: &str,
+    args: &[Expr],
+    arity: usize,
+    env: &mut Env,
+) -> Result<(Regex, String), SpressoError> {
+    check_arity(name, args, arity, arity)?;
+    let regex = extract_regex(args[0].clone(), env)?;
+    let string = extract_string(args[1].clone(), env)?;
+    Ok((regex, string))
+}
+
+fn string_expr(string: &str) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::String(string.to_string())))
+}
+
+fn unit_expr() -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Unit))
+}
+
+/// Compiles a pattern into a regex. Compile it once and reuse it (in loops, for example) to
+/// avoid compiling it again and again.
+/// # Usage
+/// `(define re (regex "(\\w+)=(\\d+)"))`
+pub fn regex(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("regex", &args, 1, 1)?;
+    let tokens = args[0].get_tokens();
+    let pattern = extract_string(args[0].clone(), env)?;
+    Ok(Expr::from(ExprKind::Regex(compile(&pattern, tokens)?)))
+}
+
+/// Whether the regex matches anywhere in the string.
+/// # Usage
+/// `(regex-match re "a=1")`
+pub fn regex_match(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-match", &args, 2, env)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Bool(
+        regex.is_match(&string),
+    ))))
+}
+
+/// The first match in the string, or `()` if there is none.
+/// # Usage
+/// `(regex-find "\\d+" "abc 123 456")`
+pub fn regex_find(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-find", &args, 2, env)?;
+    Ok(regex
+        .find(&string)
+        .map(|found| string_expr(found.as_str()))
+        .unwrap_or_else(unit_expr))
+}
+
+/// List of all (non-overlapping) matches in the string.
+/// # Usage
+/// `(regex-find-all "\\d+" "abc 123 456")`
+pub fn regex_find_all(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-find-all", &args, 2, env)?;
+    Ok(Expr::from(ExprKind::List(
+        regex
+            .find_iter(&string)
+            .map(|found| string_expr(found.as_str()))
+            .collect(),
+    )))
+}
+
+/// Capture groups of the first match as a list, starting with the entire match.
+/// A group which did not take part in the match is `()`.
+/// Returns `()` if the regex does not match.
+/// # Usage
+/// `(regex-captures "(\\w+)=(\\d+)" "a=1")`
+pub fn regex_captures(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-captures", &args, 2, env)?;
+    Ok(match regex.captures(&string) {
+        Some(captures) => Expr::from(ExprKind::List(
+            captures
+                .iter()
+                .map(|group| {
+                    group
+                        .map(|group| string_expr(group.as_str()))
+                        .unwrap_or_else(unit_expr)
+                })
+                .collect(),
+        )),
+        None => unit_expr(),
+    })
+}
+
+/// Replaces all matches with the replacement. `$1` or `${name}` in the replacement refer to
+/// capture groups.
+/// # Usage
+/// `(regex-replace "(\\w+)=(\\d+)" "a=1" "$2=$1")`
+pub fn regex_replace(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-replace", &args, 3, env)?;
+    let replacement = extract_string(args[2].clone(), env)?;
+    Ok(string_expr(
+        &regex.replace_all(&string, replacement.as_str()),
+    ))
+}
+
+/// Splits the string at every match.
+/// # Usage
+/// `(regex-split "\\s*,\\s*" "a , b,c")`
+pub fn regex_split(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (regex, string) = regex_and_string("regex-split", &args, 2, env)?;
+    Ok(Expr::from(ExprKind::List(
+        regex.split(&string).map(string_expr).collect(),
+    )))
+}
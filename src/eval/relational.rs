@@ -1,108 +1,186 @@
+use std::cmp::Ordering;
+use std::mem;
+
 use crate::{
     ast::{Atom, Expr, ExprKind},
     env::Env,
-    errors::{SpressoError, SyntaxError},
-    eval::{execute_single, extract_num},
+    errors::{RuntimeError, SpressoError},
+    eval::{check_arity, check_min_arity, execute_single, object_pairs},
+    TokenGiver, TokenHoarder,
 };
 
-pub fn lt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call < with more than 2 args",
-        )));
+/// Orders two values. Numbers are ordered by value, strings, symbols and lists
+/// lexicographically. `None` when the values are not ordered, like NaN and anything else.
+///
+/// Values of different types, like a string and a number, are never converted into each other,
+/// comparing them is an error. So is comparing values which have no order, like bools.
+fn compare(lhs: &Expr, rhs: &Expr) -> Result<Option<Ordering>, SpressoError> {
+    match (&lhs.kind, &rhs.kind) {
+        (ExprKind::Atom(Atom::Number(lhs)), ExprKind::Atom(Atom::Number(rhs))) => {
+            Ok(lhs.partial_cmp(rhs))
+        }
+        (ExprKind::Atom(Atom::String(lhs)), ExprKind::Atom(Atom::String(rhs)))
+        | (ExprKind::Atom(Atom::Symbol(lhs)), ExprKind::Atom(Atom::Symbol(rhs))) => {
+            Ok(lhs.partial_cmp(rhs))
+        }
+        (ExprKind::List(lhs), ExprKind::List(rhs)) => {
+            for (lhs, rhs) in lhs.iter().zip(rhs) {
+                match compare(lhs, rhs)? {
+                    Some(Ordering::Equal) => continue,
+                    ord => return Ok(ord),
+                }
+            }
+            // one is a prefix of the other, so the shorter one is smaller
+            Ok(lhs.len().partial_cmp(&rhs.len()))
+        }
+        (lhs, rhs) if lhs.type_name() == rhs.type_name() => {
+            Err(SpressoError::from(RuntimeError::from(format!(
+                "Cannot compare {} values, they are unordered",
+                lhs.type_name()
+            ))))
+        }
+        (lhs, rhs) => Err(SpressoError::from(RuntimeError::from(format!(
+            "Cannot compare a {} with a {}",
+            lhs.type_name(),
+            rhs.type_name()
+        )))),
     }
-
-    // execute the statements and get the results
-    let first = execute_single(args[0].clone(), env)?;
-    let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first < second)).into())
 }
 
-pub fn lteq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call <= with more than 2 args",
-        )));
+/// Evaluates the args one by one and checks that `holds` is true for every pair of adjacent
+/// args. Stops at the first pair for which it is not, without evaluating the rest.
+fn chain<F>(name: &str, args: Vec<Expr>, env: &mut Env, holds: F) -> Result<bool, SpressoError>
+where
+    F: Fn(&Expr, &Expr) -> Result<bool, SpressoError>,
+{
+    check_min_arity(name, &args, 2)?;
+    let mut prev = execute_single(args[0].clone(), env)?;
+    for pair in args.windows(2) {
+        let next = execute_single(pair[1].clone(), env)?;
+        let res = holds(&prev, &next).map_err(|err| err.maybe_with_tokens(pair.get_tokens()))?;
+        if !res {
+            return Ok(false);
+        }
+        prev = next;
     }
+    Ok(true)
+}
 
-    // execute the statements and get the results
-    let first = execute_single(args[0].clone(), env)?;
-    let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first <= second)).into())
+/// Checks if the ordering of every pair of adjacent args is one of the accepted ones.
+fn order(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+    accept: fn(Ordering) -> bool,
+) -> Result<Expr, SpressoError> {
+    let res = chain(name, args, env, |lhs, rhs| {
+        Ok(compare(lhs, rhs)?.is_some_and(accept))
+    })?;
+    Ok(ExprKind::Atom(Atom::Bool(res)).into())
 }
 
-pub fn gt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call > with more than 2 args",
-        )));
-    }
+/// Numbers are compared by value, strings and lists lexicographically.
+/// More than two args are compared in a chain, `(< a b c)` is `(and (< a b) (< b c))`.
+/// # Usage
+/// `(< "apple" "banana")` or `(< 0 x 10)`
+pub fn lt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    order("<", args, env, |ord| ord == Ordering::Less)
+}
 
-    // execute the statements and get the results
-    let first = execute_single(args[0].clone(), env)?;
-    let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first > second)).into())
+/// # Usage
+/// `(<= 1 2)`
+pub fn lteq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    order("<=", args, env, |ord| ord != Ordering::Greater)
 }
 
+/// # Usage
+/// `(> ('(1 2 3)) ('(1 2)))`
+pub fn gt(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    order(">", args, env, |ord| ord == Ordering::Greater)
+}
+
+/// # Usage
+/// `(>= 2 1)`
 pub fn gteq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call >= with more than 2 args",
-        )));
-    }
+    order(">=", args, env, |ord| ord != Ordering::Less)
+}
 
-    // execute the statements and get the results
+/// Evaluates both args of a `(<fn> lhs rhs)` call.
+fn eval_pair(name: &str, args: &[Expr], env: &mut Env) -> Result<(Expr, Expr), SpressoError> {
+    check_arity(name, args, 2, 2)?;
     let first = execute_single(args[0].clone(), env)?;
     let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first >= second)).into())
+    Ok((first, second))
 }
 
-pub fn eq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call == with more than 2 args",
-        )));
+/// Structural equality. Objects, the lists `json-parse` returns, are equal when they have the
+/// same pairs in any order.
+fn is_equal(lhs: &Expr, rhs: &Expr) -> bool {
+    match (&lhs.kind, &rhs.kind) {
+        (ExprKind::List(lhs), ExprKind::List(rhs)) => {
+            match (object_pairs("==", lhs), object_pairs("==", rhs)) {
+                (Ok(Some(lhs)), Ok(Some(rhs))) => {
+                    let contains = |pairs: &[(&str, &Expr)], (key, value): &(&str, &Expr)| {
+                        pairs.iter().any(|(k, v)| k == key && is_equal(v, value))
+                    };
+                    lhs.len() == rhs.len()
+                        && lhs.iter().all(|pair| contains(&rhs, pair))
+                        && rhs.iter().all(|pair| contains(&lhs, pair))
+                }
+                _ => {
+                    lhs.len() == rhs.len()
+                        && lhs.iter().zip(rhs).all(|(lhs, rhs)| is_equal(lhs, rhs))
+                }
+            }
+        }
+        _ => lhs == rhs,
     }
+}
 
-    // execute the statements and get the results
-    let first = execute_single(args[0].clone(), env)?;
-    let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first == second)).into())
+/// Structural equality: strings, bools, units and symbols are equal when they have the same
+/// value, lists when their elements are equal and numbers when they have the same value, even
+/// if one is an integer and the other a float. Objects are equal when they have the same pairs,
+/// whatever their order. With more than two args, all of them have to be equal.
+///
+/// Like the ordering operators, values of different types are never converted into each other,
+/// so `(== "1" 1)` is false just like `(<= "1" 1)` is an error. Values which are ordered are equal
+/// exactly when both `<=` and `>=` hold for them, except objects with their pairs in a
+/// different order.
+/// # Usage
+/// `(== ('(1 "a")) ('(1.0 "a")))`
+pub fn eq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let res = chain("==", args, env, |lhs, rhs| Ok(is_equal(lhs, rhs)))?;
+    Ok(ExprKind::Atom(Atom::Bool(res)).into())
 }
 
+/// The opposite of `==`: true when not all of the args are equal. Like `==`, it stops
+/// evaluating the args at the first one which differs from the one before.
+/// # Usage
+/// `(!= "a" "b")` or `(!= x y z)`
 pub fn neq(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
-    if args.len() != 2 {
-        return Err(SpressoError::from(SyntaxError::from(
-            "Tried to call != with more than 2 args",
-        )));
-    }
+    let res = chain("!=", args, env, |lhs, rhs| Ok(is_equal(lhs, rhs)))?;
+    Ok(ExprKind::Atom(Atom::Bool(!res)).into())
+}
 
-    // execute the statements and get the results
-    let first = execute_single(args[0].clone(), env)?;
-    let second = execute_single(args[1].clone(), env)?;
-    // override by trying to extract num
-    let first = extract_num(first, env)?;
-    let second = extract_num(second, env)?;
-    // return result
-    Ok(ExprKind::Atom(Atom::Bool(first != second)).into())
+/// Identity: true only when the two values cannot be told apart. Unlike `==`, numbers must also
+/// be of the same kind (`(eq? 2 2.0)` is false) and lambdas must be the very same lambda, not
+/// just lambdas with the same code.
+/// # Usage
+/// `(eq? ('a) ('a))`
+pub fn identical(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (first, second) = eval_pair("eq?", &args, env)?;
+    Ok(ExprKind::Atom(Atom::Bool(is_identical(&first, &second))).into())
+}
+
+fn is_identical(lhs: &Expr, rhs: &Expr) -> bool {
+    match (&lhs.kind, &rhs.kind) {
+        (ExprKind::Atom(Atom::Number(lhs)), ExprKind::Atom(Atom::Number(rhs))) => {
+            mem::discriminant(lhs) == mem::discriminant(rhs) && lhs == rhs
+        }
+        (ExprKind::List(lhs), ExprKind::List(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(l, r)| is_identical(l, r))
+        }
+        (ExprKind::Lambda(lhs), ExprKind::Lambda(rhs)) => lhs.is_same(rhs),
+        (lhs, rhs) => lhs == rhs,
+    }
 }
@@ -0,0 +1,282 @@
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{NumericError, RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_num},
+    TokenGiver, TokenHoarder,
+};
+
+/// Evaluates the expression and extracts a string out of it.
+pub fn extract_string(expr: Expr, env: &mut Env) -> Result<String, SpressoError> {
+    let tokens = expr.get_tokens();
+    let res = execute_single(expr, env)?;
+    match res.kind {
+        ExprKind::Atom(Atom::String(string)) => Ok(string),
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "Expected a string, got: {}",
+            res
+        )))
+        .maybe_with_tokens(tokens)),
+    }
+}
+
+/// Evaluates the expression and extracts a non-negative integer out of it, to be used as an
+/// index or a count.
+fn extract_index(expr: Expr, env: &mut Env) -> Result<usize, SpressoError> {
+    let tokens = expr.get_tokens();
+    match extract_num(expr, env)? {
+        Number::Int(num) if num >= 0 => Ok(num as usize),
+        num => Err(SpressoError::from(RuntimeError::from(format!(
+            "Expected a non-negative integer, got: {}",
+            num
+        )))
+        .maybe_with_tokens(tokens)),
+    }
+}
+
+/// Longest string, in bytes, that builtins like `repeat` will build, so that a huge count is an
+/// error instead of exhausting memory.
+pub(crate) const MAX_STRING_LEN: usize = 1 << 28;
+
+fn string_expr(string: String) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::String(string)))
+}
+
+fn bool_expr(boolean: bool) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Bool(boolean)))
+}
+
+fn int_expr(num: usize) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Number(Number::Int(num as i64))))
+}
+
+/// Number of characters (not bytes) in a string.
+/// # Usage
+/// `(string-length "hello")`
+pub fn string_length(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("string-length", &args, 1, 1)?;
+    let string = extract_string(args[0].clone(), env)?;
+    Ok(int_expr(string.chars().count()))
+}
+
+/// Characters from `start` (inclusive) till `end` (exclusive, defaults to end of the string).
+/// # Usage
+/// `(substring "hello" 1 3)`
+pub fn substring(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("substring", &args, 2, 3)?;
+    let string = extract_string(args[0].clone(), env)?;
+    let len = string.chars().count();
+    let start = extract_index(args[1].clone(), env)?;
+    let end = match args.get(2) {
+        Some(end) => extract_index(end.clone(), env)?,
+        None => len,
+    };
+
+    if start > end || end > len {
+        return Err(SpressoError::from(RuntimeError::from(format!(
+            "substring: range {}..{} is out of bounds for a string of length {}",
+            start, end, len
+        )))
+        .maybe_with_tokens(args.get_tokens()));
+    }
+
+    Ok(string_expr(
+        string.chars().skip(start).take(end - start).collect(),
+    ))
+}
+
+/// Joins any number of strings together.
+/// # Usage
+/// `(string-append "hello" " " "world")`
+pub fn string_append(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let mut res = String::new();
+    for arg in args {
+        res.push_str(&extract_string(arg, env)?);
+    }
+    Ok(string_expr(res))
+}
+
+/// Splits a string by a separator into a list of strings.
+/// Splits by whitespace when no separator is given, and into characters when the separator is
+/// empty.
+/// # Usage
+/// `(split "a,b,c" ",")`
+pub fn split(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("split", &args, 1, 2)?;
+    let string = extract_string(args[0].clone(), env)?;
+    let parts: Vec<String> = match args.get(1) {
+        None => string.split_whitespace().map(String::from).collect(),
+        Some(sep) => {
+            let sep = extract_string(sep.clone(), env)?;
+            if sep.is_empty() {
+                string.chars().map(String::from).collect()
+            } else {
+                string.split(sep.as_str()).map(String::from).collect()
+            }
+        }
+    };
+
+    Ok(Expr::from(ExprKind::List(
+        parts.into_iter().map(string_expr).collect(),
+    )))
+}
+
+/// Joins a list of strings with a separator (defaults to an empty string).
+/// # Usage
+/// `(join ('("a" "b" "c")) ",")`
+pub fn join(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("join", &args, 1, 2)?;
+    let list = execute_single(args[0].clone(), env)?;
+    let sep = match args.get(1) {
+        Some(sep) => extract_string(sep.clone(), env)?,
+        None => String::new(),
+    };
+
+    if let ExprKind::List(list) = list.kind {
+        let parts: Result<Vec<String>, SpressoError> = list
+            .into_iter()
+            .map(|ele| match ele.kind {
+                ExprKind::Atom(Atom::String(string)) => Ok(string),
+                _ => Err(SpressoError::from(RuntimeError::from(format!(
+                    "join: expected a list of strings, found: {}",
+                    ele
+                )))
+                .maybe_with_tokens(ele.get_tokens())),
+            })
+            .collect();
+        Ok(string_expr(parts?.join(&sep)))
+    } else {
+        Err(SpressoError::from(RuntimeError::from(
+            "join: expected list as input got something else",
+        ))
+        .maybe_with_tokens(args[0].get_tokens()))
+    }
+}
+
+/// Removes leading and trailing whitespace.
+/// # Usage
+/// `(trim "  hello  ")`
+pub fn trim(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("trim", &args, 1, 1)?;
+    let string = extract_string(args[0].clone(), env)?;
+    Ok(string_expr(string.trim().to_string()))
+}
+
+/// # Usage
+/// `(upcase "hello")`
+pub fn upcase(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("upcase", &args, 1, 1)?;
+    let string = extract_string(args[0].clone(), env)?;
+    Ok(string_expr(string.to_uppercase()))
+}
+
+/// # Usage
+/// `(downcase "HELLO")`
+pub fn downcase(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("downcase", &args, 1, 1)?;
+    let string = extract_string(args[0].clone(), env)?;
+    Ok(string_expr(string.to_lowercase()))
+}
+
+/// Evaluates both args of a `(<fn> string other)` call as strings.
+fn string_pair(
+    name: &str,
+    args: Vec<Expr>,
+    env: &mut Env,
+) -> Result<(String, String), SpressoError> {
+    check_arity(name, &args, 2, 2)?;
+    let string = extract_string(args[0].clone(), env)?;
+    let other = extract_string(args[1].clone(), env)?;
+    Ok((string, other))
+}
+
+/// # Usage
+/// `(starts-with? "hello" "he")`
+pub fn starts_with(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (string, prefix) = string_pair("starts-with?", args, env)?;
+    Ok(bool_expr(string.starts_with(&prefix)))
+}
+
+/// # Usage
+/// `(ends-with? "hello" "lo")`
+pub fn ends_with(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (string, suffix) = string_pair("ends-with?", args, env)?;
+    Ok(bool_expr(string.ends_with(&suffix)))
+}
+
+/// # Usage
+/// `(contains? "hello" "ell")`
+pub fn contains(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (string, needle) = string_pair("contains?", args, env)?;
+    Ok(bool_expr(string.contains(&needle)))
+}
+
+/// Character index of the first occurrence of a substring, or -1 if it is not found.
+/// # Usage
+/// `(index-of "hello" "l")`
+pub fn index_of(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let (string, needle) = string_pair("index-of", args, env)?;
+    let index = match string.find(&needle) {
+        Some(byte_index) => string[..byte_index].chars().count() as i64,
+        None => -1,
+    };
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(Number::Int(index)))))
+}
+
+/// Replaces all occurrences of a substring.
+/// # Usage
+/// `(replace "hello" "l" "L")`
+pub fn replace(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("replace", &args, 3, 3)?;
+    let string = extract_string(args[0].clone(), env)?;
+    let from = extract_string(args[1].clone(), env)?;
+    let to = extract_string(args[2].clone(), env)?;
+    Ok(string_expr(string.replace(&from, &to)))
+}
+
+/// Repeats a string `n` times.
+/// # Usage
+/// `(repeat "ab" 3)`
+pub fn repeat(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("repeat", &args, 2, 2)?;
+    let string = extract_string(args[0].clone(), env)?;
+    let times = extract_index(args[1].clone(), env)?;
+    match string.len().checked_mul(times) {
+        Some(len) if len <= MAX_STRING_LEN => Ok(string_expr(string.repeat(times))),
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "repeat: the result would be longer than {} bytes",
+            MAX_STRING_LEN
+        )))
+        .maybe_with_tokens(args.get_tokens())),
+    }
+}
+
+/// Parses a string into a number. Unlike `number`, only accepts strings.
+/// # Usage
+/// `(string->number "42")`
+pub fn string_to_number(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("string->number", &args, 1, 1)?;
+    let tokens = args[0].get_tokens();
+    let string = extract_string(args[0].clone(), env)?;
+
+    let num = match Number::parse(string.trim()) {
+        Some(num) => num,
+        None => {
+            return Err(SpressoError::from(NumericError::from(format!(
+                "Could not parse number from string: \"{}\"",
+                string
+            )))
+            .maybe_with_tokens(tokens))
+        }
+    };
+
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(num))))
+}
+
+/// # Usage
+/// `(number->string 42)`
+pub fn number_to_string(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("number->string", &args, 1, 1)?;
+    let num = extract_num(args[0].clone(), env)?;
+    Ok(string_expr(num.to_string()))
+}
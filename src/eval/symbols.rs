@@ -0,0 +1,71 @@
+use crate::{
+    ast::{Atom, Expr, ExprKind},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_string},
+    TokenGiver, TokenHoarder,
+};
+
+fn symbol_expr(symbol: String) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Symbol(symbol)))
+}
+
+/// Whether the value is a symbol. Use a quote to get a symbol as a value.
+/// # Usage
+/// `(symbol? (' red))`
+pub fn is_symbol(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("symbol?", &args, 1, 1)?;
+    let value = execute_single(args[0].clone(), env)?;
+    Ok(Expr::from(ExprKind::Atom(Atom::Bool(matches!(
+        value.kind,
+        ExprKind::Atom(Atom::Symbol(_))
+    )))))
+}
+
+/// Name of a symbol as a string.
+/// # Usage
+/// `(symbol->string (' red))`
+pub fn symbol_to_string(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("symbol->string", &args, 1, 1)?;
+    let value = execute_single(args[0].clone(), env)?;
+    match value.kind {
+        ExprKind::Atom(Atom::Symbol(symbol)) => {
+            Ok(Expr::from(ExprKind::Atom(Atom::String(symbol))))
+        }
+        _ => Err(SpressoError::from(RuntimeError::from(format!(
+            "Expected a symbol, got: {}",
+            value
+        )))
+        .maybe_with_tokens(args[0].get_tokens())),
+    }
+}
+
+/// Symbol with the given name. The symbol is not looked up.
+/// # Usage
+/// `(string->symbol "red")`
+pub fn string_to_symbol(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("string->symbol", &args, 1, 1)?;
+    let name = extract_string(args[0].clone(), env)?;
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        return Err(SpressoError::from(RuntimeError::from(format!(
+            "Not a valid symbol name: \"{}\"",
+            name
+        )))
+        .maybe_with_tokens(args[0].get_tokens()));
+    }
+    Ok(symbol_expr(name))
+}
+
+/// A new symbol which is different from every other symbol generated in this env, optionally
+/// starting with a prefix (`G` by default). Useful for generating code without clashing with
+/// the names in it.
+/// # Usage
+/// `(gensym)` or `(gensym "tmp")`
+pub fn gensym(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("gensym", &args, 0, 1)?;
+    let prefix = match args.first() {
+        Some(prefix) => extract_string(prefix.clone(), env)?,
+        None => "G".to_string(),
+    };
+    Ok(symbol_expr(format!("{}__{}", prefix, env.next_gensym_id())))
+}
@@ -0,0 +1,167 @@
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, NaiveDate, NaiveDateTime, SecondsFormat, Utc,
+};
+use num_traits::ToPrimitive;
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{check_arity, execute_single, extract_integer, extract_num, extract_string},
+    TokenGiver, TokenHoarder,
+};
+
+fn num_expr(num: Number) -> Expr {
+    Expr::from(ExprKind::Atom(Atom::Number(num)))
+}
+
+fn time_error(message: String, args: &[Expr]) -> SpressoError {
+    SpressoError::from(RuntimeError::from(message)).maybe_with_tokens(args.get_tokens())
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Milliseconds since the Unix epoch, in UTC.
+/// # Usage
+/// `(current-time)`
+pub fn current_time(args: Vec<Expr>, _: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("current-time", &args, 0, 0)?;
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| time_error(format!("current-time: {}", err), &args))?;
+    Ok(num_expr(Number::Int(since_epoch.as_millis() as i64)))
+}
+
+/// Milliseconds since the env was created, as a float. Unlike `current-time`, it never goes
+/// back, so the difference of two calls is a reliable duration.
+/// # Usage
+/// `(monotonic-time)`
+pub fn monotonic_time(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("monotonic-time", &args, 0, 0)?;
+    Ok(num_expr(Number::Float(millis(env.created_at().elapsed()))))
+}
+
+/// Pauses the program for the given number of milliseconds.
+/// # Usage
+/// `(sleep 250)`
+pub fn sleep(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("sleep", &args, 1, 1)?;
+    let num = extract_num(args[0].clone(), env)?;
+    let ms = num.to_f64();
+    if ms.is_nan() || ms < 0.0 {
+        return Err(time_error(
+            format!("sleep: expected a non-negative duration, got: {}", num),
+            &args,
+        ));
+    }
+    let duration = Duration::try_from_secs_f64(ms / 1000.0)
+        .map_err(|_| time_error("sleep: the duration is too long".to_string(), &args))?;
+    thread::sleep(duration);
+    Ok(Expr::from(ExprKind::Atom(Atom::Unit)))
+}
+
+/// Evaluates the expressions one after the other and returns how long that took, in
+/// milliseconds.
+/// # Usage
+/// `(elapsed-time (define data (read-file "big.csv")) (csv-read data))`
+pub fn elapsed_time(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    let start = Instant::now();
+    for arg in args {
+        execute_single(arg, env)?;
+    }
+    Ok(num_expr(Number::Float(millis(start.elapsed()))))
+}
+
+/// The strftime items of a format, checked so that formatting with them cannot fail.
+fn format_items<'a>(
+    name: &str,
+    format: &'a str,
+    args: &[Expr],
+) -> Result<Vec<Item<'a>>, SpressoError> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(time_error(
+            format!("{}: invalid format `{}`", name, format),
+            args,
+        ));
+    }
+    Ok(items)
+}
+
+/// Formats a time in milliseconds since the epoch as a UTC date. Without a format, the date is
+/// in ISO-8601, like `2024-03-01T12:30:00.000Z`. Otherwise the format uses strftime
+/// placeholders, like `%Y-%m-%d %H:%M`.
+/// # Usage
+/// `(format-time (current-time) "%d/%m/%Y")`
+pub fn format_time(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("format-time", &args, 1, 2)?;
+    let ms = extract_integer("format-time", args[0].clone(), env)?;
+    let time = ms
+        .to_i64()
+        .and_then(DateTime::<Utc>::from_timestamp_millis)
+        .ok_or_else(|| time_error(format!("format-time: {} is out of range", ms), &args))?;
+
+    let res = match args.get(1) {
+        Some(format) => {
+            let format = extract_string(format.clone(), env)?;
+            let items = format_items("format-time", &format, &args)?;
+            time.format_with_items(items.into_iter()).to_string()
+        }
+        None => time.to_rfc3339_opts(SecondsFormat::Millis, true),
+    };
+    Ok(Expr::from(ExprKind::Atom(Atom::String(res))))
+}
+
+/// Parses an ISO-8601 date. Dates without an offset are taken to be in UTC, dates without a
+/// time are at midnight.
+fn parse_iso(text: &str) -> Option<i64> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.timestamp_millis());
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(time.and_utc().timestamp_millis());
+    }
+    let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis())
+}
+
+/// Parses a date with a strftime format, in UTC. A format without a time gives midnight.
+fn parse_with_format(text: &str, format: &str) -> Option<i64> {
+    if let Ok(time) = NaiveDateTime::parse_from_str(text, format) {
+        return Some(time.and_utc().timestamp_millis());
+    }
+    let date = NaiveDate::parse_from_str(text, format).ok()?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis())
+}
+
+/// Parses a date into milliseconds since the epoch. The reverse of `format-time`: without a
+/// format, the date must be in ISO-8601, otherwise it must match the strftime format.
+/// # Usage
+/// `(parse-time "2024-03-01T12:30:00Z")` or `(parse-time "01/03/2024" "%d/%m/%Y")`
+pub fn parse_time(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
+    check_arity("parse-time", &args, 1, 2)?;
+    let text = extract_string(args[0].clone(), env)?;
+    let (ms, expected) = match args.get(1) {
+        Some(format) => {
+            let format = extract_string(format.clone(), env)?;
+            format_items("parse-time", &format, &args)?;
+            let expected = format!("the format `{}`", format);
+            (parse_with_format(&text, &format), expected)
+        }
+        None => (parse_iso(&text), "ISO-8601".to_string()),
+    };
+
+    match ms {
+        Some(ms) => Ok(num_expr(Number::Int(ms))),
+        None => Err(time_error(
+            format!("parse-time: `{}` is not a date in {}", text, expected),
+            &args,
+        )),
+    }
+}
@@ -1,12 +1,12 @@
 use crate::{
-    ast::{Expr, ExprKind, Atom},
+    ast::{Expr, ExprKind, Atom, Number},
     env::Env,
-    errors::{SpressoError, RuntimeError},
+    errors::{SpressoError, RuntimeError, NumericError},
     TokenGiver,
     TokenHoarder
 };
 
-use super::extract_num;
+use super::execute_single;
 
 pub fn cast_as_num(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError> {
     if args.len() != 1 {
@@ -14,5 +14,28 @@ pub fn cast_as_num(args: Vec<Expr>, env: &mut Env) -> Result<Expr, SpressoError>
             "number needs an expression to cast into a number",
         )).maybe_with_tokens(args.get_tokens()));
     }
-    return Ok(Expr::from(ExprKind::Atom(Atom::Number(extract_num(args[0].clone(), env)?))));
+    // an explicit conversion, so strings are converted even if the env does not do it
+    // implicitly
+    let tokens = args[0].get_tokens();
+    let res = execute_single(args[0].clone(), env)?;
+    let num = match res.kind {
+        ExprKind::Atom(Atom::Number(num)) => num,
+        ExprKind::Atom(Atom::String(ref string)) => Number::parse(string).ok_or_else(|| {
+            SpressoError::from(NumericError::from(format!(
+                "Could not parse number from string: \"{}\"",
+                string
+            )))
+            .maybe_with_tokens(tokens)
+        })?,
+        // the value is not evaluated again, a quoted list or symbol is not a number
+        ref kind => {
+            return Err(SpressoError::from(NumericError::from(format!(
+                "Cannot cast a {} into a number: {}",
+                kind.type_name(),
+                res
+            )))
+            .maybe_with_tokens(tokens))
+        }
+    };
+    Ok(Expr::from(ExprKind::Atom(Atom::Number(num))))
 }
@@ -1,7 +1,10 @@
 pub mod ast;
+pub mod convert;
 pub mod env;
 pub mod errors;
 pub mod eval;
+#[cfg(feature = "serde")]
+mod serde_impl;
 mod utils;
 
 use core::fmt;
@@ -67,10 +70,15 @@ pub struct Program {
 #[derive(Clone, Debug)]
 pub struct Token {
     text: String,
-    // TODO: some tokens like string could go across multiple lines
-    // store both line_num_start and line_num_end
-    line_num: usize,
+    /// Line on which the token starts.
+    line_num_start: usize,
+    /// Line on which the token ends.
+    ///
+    /// Same as `line_num_start` except for tokens that span multiple lines, like strings.
+    line_num_end: usize,
+    /// Column (on `line_num_start`) at which the token starts.
     col_num_start: usize,
+    /// Column (on `line_num_end`) just after the token ends.
     col_num_end: usize,
     program: Rc<Program>,
     type_: TokenType,
@@ -97,8 +105,28 @@ fn display_and_mark(f: &mut fmt::Formatter<'_>, tokens: &[Token]) -> fmt::Result
             .entry(program_key)
             .or_insert((Rc::clone(&token.program), BTreeMap::<usize, Ranges>::new()));
 
-        let ranges = line_map.entry(token.line_num).or_default();
-        ranges.push(token.col_num_start..=token.col_num_end);
+        if token.line_num_start == token.line_num_end {
+            let ranges = line_map.entry(token.line_num_start).or_default();
+            ranges.push(token.col_num_start..=token.col_num_end);
+            return;
+        }
+
+        // a token spanning multiple lines is marked from its start till the end of the first
+        // line, entirely on the lines in between and till its end on the last line. A string
+        // left open at the end of the program can end on the empty line after a trailing
+        // newline, which is not in `lines`, so it is only marked up to the last line.
+        let last_line_num = token.line_num_end.min(token.program.lines.len());
+        for line_num in token.line_num_start..=last_line_num {
+            let line_end = token.program.lines[line_num - 1].chars().count() + 1;
+            let range = if line_num == token.line_num_start {
+                token.col_num_start..=line_end
+            } else if line_num == token.line_num_end {
+                1..=token.col_num_end
+            } else {
+                1..=line_end
+            };
+            line_map.entry(line_num).or_default().push(range);
+        }
     });
 
     for (_, (program, line_map)) in program_line_map.iter() {
@@ -143,6 +171,11 @@ enum TokenType {
     Unit,
 }
 
+/// Characters which end a number or a symbol.
+fn is_delimiter(c: &char) -> bool {
+    matches!(c, ' ' | '\n' | '(' | ')')
+}
+
 fn tokenize(program: Rc<Program>) -> VecDeque<Token> {
     let mut tokens = VecDeque::new();
 
@@ -167,10 +200,15 @@ fn tokenize(program: Rc<Program>) -> VecDeque<Token> {
                 }
             }
             ')' => Some((new_token, TokenType::CloseParen)),
-            '0'..='9' | '.' => {
-                // takes as long as numbers are found
-                // TODO: stop only at whitespace
-                let new_chars = chars.peeking_take_while(|c| matches!(c, '0'..='9' | '.'));
+            // a quote is always a symbol by itself, so that `'foo` is the same as `' foo`
+            '\'' => Some((new_token, TokenType::Symbol)),
+            // a sign is part of the number only when it is followed by one, `-` alone is a symbol
+            '0'..='9' | '.' | '-' | '+'
+                if c.is_ascii_digit() || matches!(chars.peek(), Some('0'..='9' | '.')) =>
+            {
+                // takes everything until some other token is found, so that the entire literal
+                // can be validated while parsing
+                let new_chars = chars.peeking_take_while(|c| !is_delimiter(c));
                 new_token.extend(new_chars);
                 Some((new_token, TokenType::Number))
             }
@@ -184,24 +222,35 @@ fn tokenize(program: Rc<Program>) -> VecDeque<Token> {
                 None
             }
             '"' => {
-                // takes everything before closing '"'
-                let new_chars = chars.peeking_take_while(|c| !matches!(c, '"'));
-                new_token.extend(new_chars);
-
-                // check if string is closed
-                if let Some('"') = chars.peek() {
-                    new_token.push(chars.next().unwrap());
+                // takes everything till the closing '"', skipping over escaped characters.
+                // escape sequences are only validated and replaced while parsing, so that
+                // errors can point to the token.
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    new_token.push(c);
+                    if escaped {
+                        escaped = false;
+                    } else if c == '\\' {
+                        escaped = true;
+                    } else if c == '"' {
+                        break;
+                    }
                 }
-                // TODO: show error if string not closed
 
                 Some((new_token, TokenType::String))
             }
             _ => {
                 // take everything until some other token is found
-                // TODO: move this set of chars somewhere else
-                let new_chars = chars.peeking_take_while(|c| !matches!(c, ' ' | '\n' | '(' | ')'));
+                let new_chars = chars.peeking_take_while(|c| !is_delimiter(c));
                 new_token.extend(new_chars);
 
+                // these are numbers even though they look like symbols
+                if matches!(
+                    new_token.as_str(),
+                    "inf" | "+inf" | "-inf" | "nan" | "+nan" | "-nan"
+                ) {
+                    return Some((new_token, TokenType::Number));
+                }
                 Some((new_token, TokenType::Symbol))
             }
         }
@@ -212,19 +261,28 @@ fn tokenize(program: Rc<Program>) -> VecDeque<Token> {
 
     // loop until chars are present
     while let Some(c) = chars.next() {
-        // record starting col number
+        // record starting line and col number
+        let line_num_start = line_num;
         let col_num_start = col_num;
 
         if let Some((new_token, type_)) = char_processor(c, &mut chars, &mut line_num, &mut col_num)
         {
-            // new col number is old + size of current token
+            // move past the current token. only strings can contain a newline.
             // when there isn't any token, char_processor handles
             // incrementing col_num
-            col_num += new_token.len();
+            for c in new_token.chars() {
+                if c == '\n' {
+                    line_num += 1;
+                    col_num = 1;
+                } else {
+                    col_num += 1;
+                }
+            }
 
             tokens.push_back(Token {
                 text: new_token,
-                line_num,
+                line_num_start,
+                line_num_end: line_num,
                 col_num_start,
                 col_num_end: col_num,
                 program: Rc::clone(&program),
@@ -271,24 +329,15 @@ fn parse(tokens: &mut VecDeque<Token>) -> Result<Expr, SpressoError> {
 
 fn parse_atom(token: Token) -> Result<Atom, SpressoError> {
     match token.type_ {
-        TokenType::Number => {
-            let text = token.text.clone();
-
-            if let Ok(num) = text.parse::<i64>() {
-                return Ok(Atom::Number(Number::Int(num)));
-            }
-
-            if let Ok(num) = text.parse::<f64>() {
-                return Ok(Atom::Number(Number::Float(num)));
-            }
-
-            Err(SpressoError::from(SyntaxError::from("Could not parse number")).with_token(token))
-        }
+        TokenType::Number => match Number::parse_literal(&token.text) {
+            Ok(num) => Ok(Atom::Number(num)),
+            Err(err) => Err(SpressoError::from(SyntaxError::from(err)).with_token(token)),
+        },
         TokenType::Unit => Ok(Atom::Unit),
-        // remove quotes from string token and store
-        TokenType::String => Ok(Atom::String(
-            token.text[1..token.text.len() - 1].to_string(),
-        )),
+        TokenType::String => match unescape_string(&token.text) {
+            Ok(string) => Ok(Atom::String(string)),
+            Err(err) => Err(SpressoError::from(err).with_token(token)),
+        },
         TokenType::Symbol => Ok(Atom::Symbol(token.text)),
         TokenType::OpenParen | TokenType::CloseParen => Err(SpressoError::from(SyntaxError::from(
             "Cannot extract atom from these lol",
@@ -297,6 +346,65 @@ fn parse_atom(token: Token) -> Result<Atom, SpressoError> {
     }
 }
 
+/// Removes the quotes around a string token and replaces the escape sequences in it.
+///
+/// Supported escape sequences are `\"`, `\\`, `\n`, `\r`, `\t` and `\u{...}` (a unicode
+/// code point in hex).
+fn unescape_string(text: &str) -> Result<String, SyntaxError> {
+    let mut string = String::new();
+    // skip the opening '"'
+    let mut chars = text.chars().skip(1);
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return Ok(string),
+            '\\' => match chars.next() {
+                Some('"') => string.push('"'),
+                Some('\\') => string.push('\\'),
+                Some('n') => string.push('\n'),
+                Some('r') => string.push('\r'),
+                Some('t') => string.push('\t'),
+                Some('u') => string.push(unescape_unicode(&mut chars)?),
+                Some(c) => {
+                    return Err(SyntaxError::from(format!(
+                        "Unknown escape sequence in string: \\{}",
+                        c
+                    )))
+                }
+                None => break,
+            },
+            _ => string.push(c),
+        }
+    }
+
+    Err(SyntaxError::from("String not closed"))
+}
+
+/// Parses the `{...}` part of a `\u{...}` escape sequence.
+fn unescape_unicode(chars: &mut impl Iterator<Item = char>) -> Result<char, SyntaxError> {
+    let invalid =
+        || SyntaxError::from("Invalid unicode escape sequence in string, expected \\u{...}");
+
+    if chars.next() != Some('{') {
+        return Err(invalid());
+    }
+
+    // at most 6 hex digits followed by a '}'
+    let mut hex = String::new();
+    loop {
+        match chars.next() {
+            Some('}') if !hex.is_empty() => break,
+            Some(c) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(c),
+            _ => return Err(invalid()),
+        }
+    }
+
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| SyntaxError::from(format!("Invalid unicode code point in string: {}", hex)))
+}
+
 trait TokenHoarder {
     fn with_token(self, token: Token) -> Self;
 
@@ -322,6 +430,7 @@ trait TokenHoarder {
         }
     }
 
+    #[allow(dead_code)]
     fn maybe_with_token(self, token: Option<Token>) -> Self
     where
         Self: Sized,
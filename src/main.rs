@@ -1,6 +1,7 @@
 use std::env;
 use std::fs::{read_to_string, File};
 use std::path::PathBuf;
+use std::process;
 
 use home::home_dir;
 use spressolisp::{env::Env, evaluate_expression};
@@ -8,7 +9,8 @@ use spressolisp::{env::Env, evaluate_expression};
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
-fn repl() {
+/// Returns the status code to exit with.
+fn repl() -> i32 {
     // readline REPL
     let mut rl = Editor::<()>::new();
 
@@ -28,6 +30,7 @@ fn repl() {
     // create the env
     let mut env = Env::new();
     let mut input_num = 0;
+    let mut exit_code = 0;
 
     // start the eval loop
     loop {
@@ -43,11 +46,19 @@ fn repl() {
                 if input == ".quit" {
                     break;
                 } else if input == ".env" {
-                    env.display();
+                    if let Err(err) = env.display() {
+                        eprintln!("{}", err);
+                    }
                 } else {
                     match evaluate_expression(format!("input[{}]", input_num), input, &mut env) {
                         Ok(res) => println!("{}", res),
-                        Err(err) => println!("{}", err),
+                        Err(err) => match err.exit_code() {
+                            Some(code) => {
+                                exit_code = code;
+                                break;
+                            }
+                            None => println!("{}", err),
+                        },
                     };
                 }
 
@@ -63,22 +74,32 @@ fn repl() {
     }
     println!("goodbye!");
     rl.save_history(&history_path).unwrap();
+    exit_code
 }
 
-fn execute_file(filepath: &str) {
+/// Runs the file with the rest of the args as `command-line-args`. Returns the status code to
+/// exit with: the one given to `exit`, or 1 if the program ran into an error.
+fn execute_file(filepath: &str, args: Vec<String>) -> i32 {
     let mut env = Env::new();
+    env.set_command_line_args(args);
 
     let contents = read_to_string(filepath).expect("Could not read file");
 
-    if let Err(err) = evaluate_expression(filepath.to_owned(), contents, &mut env) {
-        println!("{}", err);
-    };
+    match evaluate_expression(filepath.to_owned(), contents, &mut env) {
+        Ok(_) => 0,
+        Err(err) => err.exit_code().unwrap_or_else(|| {
+            println!("{}", err);
+            1
+        }),
+    }
 }
 
 fn main() {
-    if let Some(filepath) = env::args().nth(1) {
-        execute_file(&filepath);
+    let mut args = env::args().skip(1);
+    let code = if let Some(filepath) = args.next() {
+        execute_file(&filepath, args.collect())
     } else {
         repl()
-    }
+    };
+    process::exit(code);
 }
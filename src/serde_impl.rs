@@ -0,0 +1,154 @@
+//! `Serialize` and `Deserialize` for values which are plain data: numbers, strings, bools,
+//! symbols, units and lists of them. Behind the `serde` feature.
+//!
+//! There is no map type, so a map is deserialized into an object, a list of `(key value)` pairs
+//! headed by the symbol `object`, just like `json-parse` and `HashMap` in `convert` do. Objects
+//! are serialized back into maps. Functions, lambdas and regexes are not data, serializing them
+//! is an error.
+
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    ser::{self, SerializeMap, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    ast::{Atom, Expr, ExprKind, Number},
+    eval::{object_pairs, OBJECT_TAG},
+};
+
+impl Serialize for Expr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.kind {
+            ExprKind::Atom(Atom::Number(num)) => num.serialize(serializer),
+            ExprKind::Atom(Atom::String(string)) | ExprKind::Atom(Atom::Symbol(string)) => {
+                serializer.serialize_str(string)
+            }
+            ExprKind::Atom(Atom::Bool(val)) => serializer.serialize_bool(*val),
+            ExprKind::Atom(Atom::Unit) => serializer.serialize_unit(),
+            ExprKind::List(exprs) => {
+                let pairs = object_pairs("serialize", exprs)
+                    .map_err(|err| ser::Error::custom(err.text()))?;
+                if let Some(pairs) = pairs {
+                    let mut map = serializer.serialize_map(Some(pairs.len()))?;
+                    for (key, value) in pairs {
+                        map.serialize_entry(key, value)?;
+                    }
+                    return map.end();
+                }
+                let mut seq = serializer.serialize_seq(Some(exprs.len()))?;
+                for expr in exprs {
+                    seq.serialize_element(expr)?;
+                }
+                seq.end()
+            }
+            kind => Err(ser::Error::custom(format!(
+                "cannot serialize a {}, only data like numbers, strings and lists can be serialized",
+                kind.type_name()
+            ))),
+        }
+    }
+}
+
+impl Serialize for Number {
+    /// Ratios have no equivalent in most formats, so they are serialized as floats.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Number::Int(int) => serializer.serialize_i64(*int),
+            Number::BigInt(int) => match (int.to_i128(), int.to_u128()) {
+                (Some(int), _) => serializer.serialize_i128(int),
+                (_, Some(int)) => serializer.serialize_u128(int),
+                _ => Err(ser::Error::custom(format!(
+                    "cannot serialize {}, it does not fit in 128 bits",
+                    int
+                ))),
+            },
+            Number::Ratio(_) | Number::Float(_) => serializer.serialize_f64(self.to_f64()),
+        }
+    }
+}
+
+struct ExprVisitor;
+
+fn atom(atom: Atom) -> Expr {
+    ExprKind::Atom(atom).into()
+}
+
+impl<'de> Visitor<'de> for ExprVisitor {
+    type Value = Expr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, string, bool, unit, list or map")
+    }
+
+    fn visit_bool<E: de::Error>(self, val: bool) -> Result<Expr, E> {
+        Ok(atom(Atom::Bool(val)))
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Expr, E> {
+        Ok(atom(Atom::Number(Number::Int(val))))
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Expr, E> {
+        Ok(atom(Atom::Number(Number::from_bigint(BigInt::from(val)))))
+    }
+
+    fn visit_i128<E: de::Error>(self, val: i128) -> Result<Expr, E> {
+        Ok(atom(Atom::Number(Number::from_bigint(BigInt::from(val)))))
+    }
+
+    fn visit_u128<E: de::Error>(self, val: u128) -> Result<Expr, E> {
+        Ok(atom(Atom::Number(Number::from_bigint(BigInt::from(val)))))
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Expr, E> {
+        Ok(atom(Atom::Number(Number::Float(val))))
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Expr, E> {
+        Ok(atom(Atom::String(val.to_string())))
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Expr, E> {
+        Ok(atom(Atom::String(val)))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Expr, E> {
+        Ok(atom(Atom::Unit))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Expr, E> {
+        Ok(atom(Atom::Unit))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Expr, D::Error> {
+        Expr::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Expr, A::Error> {
+        let mut exprs = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(expr) = seq.next_element()? {
+            exprs.push(expr);
+        }
+        Ok(ExprKind::List(exprs).into())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Expr, A::Error> {
+        let mut pairs = Vec::with_capacity(map.size_hint().unwrap_or(0) + 1);
+        pairs.push(atom(Atom::Symbol(OBJECT_TAG.to_string())));
+        while let Some((key, val)) = map.next_entry::<String, Expr>()? {
+            pairs.push(ExprKind::List(vec![atom(Atom::String(key)), val]).into());
+        }
+        Ok(ExprKind::List(pairs).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Expr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ExprVisitor)
+    }
+}
@@ -1 +1,2 @@
 pub mod range_stack;
+pub mod rng;
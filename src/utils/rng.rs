@@ -0,0 +1,51 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// A small and fast pseudo-random number generator (SplitMix64).
+///
+/// Not meant for cryptography. The same seed always gives the same sequence of numbers on every
+/// platform, which is what makes seeded scripts reproducible.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// A generator with a different seed every time.
+    pub fn from_entropy() -> Self {
+        // every RandomState is created with different keys, so this is a cheap source of
+        // randomness without any dependencies
+        Rng::new(RandomState::new().build_hasher().finish())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        // ref: https://prng.di.unimi.it/splitmix64.c
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`, with every number equally likely. `bound` must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        // numbers less than `2^64 % bound` are rejected, so that `r % bound` is not biased
+        // towards smaller numbers
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let r = self.next_u64();
+            if r >= threshold {
+                return r % bound;
+            }
+        }
+    }
+
+    /// A float in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        // the top 53 bits fill the mantissa of a float exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
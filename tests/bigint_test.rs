@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{check_conditional, check_float_expr, check_integer_expr, check_string_expr_in_env};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_overflow_promotes_to_bigint() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string (* 9223372036854775807 2))",
+        "18446744073709551614",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        "(number->string (+ 9223372036854775807 1))",
+        "9223372036854775808",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        "(number->string (- (- 0 9223372036854775807) 10))",
+        "-9223372036854775817",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_bigint_demotes_when_it_fits() {
+    check_integer_expr(
+        "(- (* 9223372036854775807 2) 9223372036854775807)",
+        9223372036854775807,
+    );
+    check_integer_expr("(/ 100000000000000000000 100000000000)", 1000000000);
+}
+
+#[test]
+fn test_bigint_literals() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string (+ 123456789012345678901234567890 1))",
+        "123456789012345678901234567891",
+        &mut env,
+    );
+    check_float_expr("(+ 100000000000000000000 0.5)", 1e20);
+}
+
+#[test]
+fn test_bigint_comparisons() {
+    check_conditional("(> 100000000000000000000 5)", true);
+    check_conditional("(< 100000000000000000000 100000000000000000001)", true);
+    check_conditional("(== 100000000000000000000 100000000000000000000.0)", true);
+    check_conditional("(== 2 2.0)", true);
+}
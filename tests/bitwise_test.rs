@@ -0,0 +1,98 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_expr_error_in_env, check_integer_expr, check_integer_expr_in_env,
+    check_string_expr_in_env,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_bitwise_logic() {
+    check_integer_expr("(bit-and 12 10)", 8);
+    check_integer_expr("(bit-or 12 10)", 14);
+    check_integer_expr("(bit-xor 12 10)", 6);
+    check_integer_expr("(bit-and 15 7 3)", 3);
+    check_integer_expr("(bit-or 1 2 4)", 7);
+    check_integer_expr("(bit-and)", -1);
+    check_integer_expr("(bit-or)", 0);
+    check_integer_expr("(bit-not 12)", -13);
+    check_integer_expr("(bit-and (- 0 1) 255)", 255);
+}
+
+#[test]
+fn test_shifts() {
+    check_integer_expr("(shift-left 1 8)", 256);
+    check_integer_expr("(shift-right 256 4)", 16);
+    check_integer_expr("(shift-right (- 0 7) 1)", -4);
+
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string (shift-left 1 64))",
+        "18446744073709551616",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(shift-left 1 (- 0 1))",
+        "shift-left: expected a non-negative shift amount, got: -1",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_popcount() {
+    check_integer_expr("(popcount 255)", 8);
+    check_integer_expr("(popcount 0)", 0);
+
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(popcount (- 0 1))",
+        "popcount: expected a non-negative integer, got: -1",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_bitwise_rejects_non_integers() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(bit-and 3 1.5)",
+        "bit-and: expected an integer, got: 1.5",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(shift-left 2.0 1)",
+        "shift-left: expected an integer, got: 2",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(bit-not 1/2)",
+        "bit-not: expected an integer, got: 1/2",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_strict_shift_overflow() {
+    let mut env = Env::new();
+    env.enable_strict_arithmetic();
+    check_expr_error_in_env("(shift-left 1 63)", "Integer overflow", &mut env);
+    check_integer_expr_in_env("(shift-left 1 62)", 4611686018427387904, &mut env);
+    check_expr_error_in_env("(shift-left 1 4000000000)", "Integer overflow", &mut env);
+    check_integer_expr_in_env("(shift-left (- 0 1) 63)", -9223372036854775808, &mut env);
+}
+
+#[test]
+fn test_huge_shift() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(shift-left 1 4000000000)",
+        "shift-left: the result would have more than 4194304 bits",
+        &mut env,
+    );
+    check_integer_expr_in_env("(shift-left 0 4000000000)", 0, &mut env);
+    check_integer_expr_in_env("(shift-right 1 4000000000)", 0, &mut env);
+}
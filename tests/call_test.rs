@@ -0,0 +1,124 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::eval_expr_in_env;
+
+use spressolisp::{
+    ast::{Atom, Expr, ExprKind},
+    convert::{FromExpr, IntoExpr},
+    env::Env,
+};
+
+fn symbol(name: &str) -> Expr {
+    ExprKind::Atom(Atom::Symbol(name.to_string())).into()
+}
+
+fn call_global<T: FromExpr>(env: &mut Env, name: &str, args: Vec<Expr>) -> T {
+    match env.call_global(name, args).and_then(T::from_expr) {
+        Ok(val) => val,
+        Err(err) => panic!("calling '{}' failed: {}", name, err),
+    }
+}
+
+fn call_global_error(env: &mut Env, name: &str, args: Vec<Expr>) -> String {
+    match env.call_global(name, args) {
+        Ok(res) => panic!("calling '{}' should have failed, got: {}", name, res),
+        Err(err) => err.text().to_string(),
+    }
+}
+
+#[test]
+fn test_call_lambda() {
+    let mut env = Env::new();
+    eval_expr_in_env("(define add (lambda (x y) (+ x y)))", &mut env);
+    eval_expr_in_env("(define first (lambda (x y) x))", &mut env);
+
+    let sum: i64 = call_global(&mut env, "add", vec![1i64.into_expr(), 2i64.into_expr()]);
+    assert_eq!(sum, 3);
+
+    // the args are not evaluated again
+    let res: Expr = call_global(&mut env, "first", vec![symbol("nope"), ().into_expr()]);
+    assert_eq!(res, symbol("nope"));
+    let res: Vec<i64> = call_global(
+        &mut env,
+        "first",
+        vec![vec![3i64, 4].into_expr(), ().into_expr()],
+    );
+    assert_eq!(res, vec![3, 4]);
+
+    // a lambda value can be called directly
+    let callback = eval_expr_in_env("(lambda (s) (concat s \"!\"))", &mut env);
+    let res = env.call(&callback, vec!["hi".into_expr()]).ok();
+    assert_eq!(
+        res.map(String::from_expr).and_then(Result::ok),
+        Some("hi!".to_string())
+    );
+}
+
+#[test]
+fn test_call_builtin() {
+    let mut env = Env::new();
+    let sum: i64 = call_global(&mut env, "+", vec![1i64.into_expr(), 2i64.into_expr()]);
+    assert_eq!(sum, 3);
+
+    // builtins get back the values as they are, even symbols and lists
+    let res: bool = call_global(&mut env, "symbol?", vec![symbol("nope")]);
+    assert!(res);
+    let res: bool = call_global(
+        &mut env,
+        "==",
+        vec![vec![1i64, 2].into_expr(), vec![1i64, 2].into_expr()],
+    );
+    assert!(res);
+
+    env.register_typed_fn("twice", |x: i64| x * 2);
+    let res: i64 = call_global(&mut env, "twice", vec![21i64.into_expr()]);
+    assert_eq!(res, 42);
+}
+
+#[test]
+fn test_call_errors() {
+    let mut env = Env::new();
+    eval_expr_in_env("(define add (lambda (x y) (+ x y)))", &mut env);
+    eval_expr_in_env("(define x 5)", &mut env);
+
+    assert_eq!(
+        call_global_error(&mut env, "add", vec![1i64.into_expr()]),
+        "Expected 2 arguments, got 1"
+    );
+    assert_eq!(
+        call_global_error(&mut env, "missing", vec![]),
+        "Symbol not found: missing"
+    );
+    assert_eq!(
+        call_global_error(&mut env, "x", vec![]),
+        "this is not something I can execute: 5 "
+    );
+}
+
+#[test]
+fn test_special_forms_cannot_be_called() {
+    let mut env = Env::new();
+    // an alias is still the same special form
+    eval_expr_in_env("(define my-if if)", &mut env);
+    for form in [
+        "define",
+        "lambda",
+        "if",
+        "loop",
+        "'",
+        "quote",
+        "and",
+        "or",
+        "elapsed-time",
+        "my-if",
+    ] {
+        assert_eq!(
+            call_global_error(&mut env, form, vec![symbol("y"), 1i64.into_expr()]),
+            "special forms cannot be called with values, only evaluated: built-in function "
+        );
+    }
+    assert!(!env.contains_key("y"));
+}
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{check_expr_error_in_env, check_integer_expr_in_env, check_string_expr_in_env};
+
+use spressolisp::env::Env;
+
+fn strict_env() -> Env {
+    let mut env = Env::new();
+    env.enable_strict_arithmetic();
+    env
+}
+
+#[test]
+fn test_strict_overflow_is_an_error() {
+    let mut env = strict_env();
+    check_expr_error_in_env("(+ 9223372036854775807 1)", "Integer overflow", &mut env);
+    check_expr_error_in_env("(* 9223372036854775807 2)", "Integer overflow", &mut env);
+    check_expr_error_in_env(
+        "(- (- 0 9223372036854775807) 10)",
+        "Integer overflow",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(/ (- (- 0 9223372036854775807) 1) (- 0 1))",
+        "Integer overflow",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_strict_rounding_overflow() {
+    let mut env = strict_env();
+    for op in ["floor", "ceil", "round", "truncate"] {
+        check_expr_error_in_env(
+            &format!("({} (/ 36893488147419103231 2))", op),
+            "Integer overflow",
+            &mut env,
+        );
+    }
+    check_expr_error_in_env("(inexact->exact 1e30)", "Integer overflow", &mut env);
+    check_integer_expr_in_env("(floor (/ 7 2))", 3, &mut env);
+}
+
+#[test]
+fn test_strict_arithmetic_within_range() {
+    let mut env = strict_env();
+    check_integer_expr_in_env("(+ 9223372036854775806 1)", 9223372036854775807, &mut env);
+    check_integer_expr_in_env("(* 3 4 5)", 60, &mut env);
+    check_string_expr_in_env("(number->string (/ 7 2))", "7/2", &mut env);
+}
+
+#[test]
+fn test_default_mode_promotes() {
+    let mut env = Env::new();
+    assert!(!env.is_strict_arithmetic());
+    check_string_expr_in_env(
+        "(number->string (+ 9223372036854775807 1))",
+        "9223372036854775808",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_missing_args_do_not_panic() {
+    let mut env = Env::new();
+    check_expr_error_in_env("(-)", "`-` needs at least 1 argument, got 0", &mut env);
+    check_expr_error_in_env("(/)", "`/` needs at least 1 argument, got 0", &mut env);
+    check_expr_error_in_env("(/ 1 0)", "Division By Zero", &mut env);
+}
@@ -1,7 +1,8 @@
 use spressolisp::{
     ast::{Atom, Expr, ExprKind, Number},
-    env::Env,
-    errors::{SpressoErrorType, SyntaxError},
+    convert::FromExpr,
+    env::{CapturedOutput, Env},
+    errors::{SpressoError, SpressoErrorType, SyntaxError},
     eval::extract_num,
     evaluate_expression,
 };
@@ -13,16 +14,32 @@ pub fn eval_expr_in_env(expr: &str, env: &mut Env) -> Expr {
     }
 }
 
-pub fn check_expr_error_in_env(expr: &str, expected_err: &str, env: &mut Env) {
+/// Evaluates the expression and converts the result, panicking if it has the wrong type.
+pub fn eval_as_in_env<T: FromExpr>(expr: &str, env: &mut Env) -> T {
+    match T::from_expr(eval_expr_in_env(expr, env)) {
+        Ok(val) => val,
+        Err(err) => panic!("Result of '{}' had the wrong type: {}", expr, err),
+    }
+}
+
+pub fn eval_as<T: FromExpr>(expr: &str) -> T {
+    eval_as_in_env(expr, &mut Env::new())
+}
+
+pub fn eval_expr_error_in_env(expr: &str, env: &mut Env) -> SpressoError {
     match evaluate_expression("test".to_string(), expr.to_string(), env) {
         Ok(res) => panic!(
             "Expression which was expected to return an error, ran successfully: {}. Result: {}",
             expr, res
         ),
-        Err(err) => assert_eq!(err.text(), expected_err),
+        Err(err) => err,
     }
 }
 
+pub fn check_expr_error_in_env(expr: &str, expected_err: &str, env: &mut Env) {
+    assert_eq!(eval_expr_error_in_env(expr, env).text(), expected_err);
+}
+
 fn eval_number_expr_in_env<T>(expr: &str, env: &mut Env, cb: T)
 where
     T: Fn(Number),
@@ -78,9 +95,15 @@ pub fn check_string_expr_in_env(expr: &str, expected: &str, env: &mut Env) {
 }
 
 pub fn check_number_syntax_err(expr: &str, expected: &str) {
+    check_syntax_err(expr, expected);
+}
+
+pub fn check_syntax_err(expr: &str, expected: &str) {
     let mut env = Env::new();
 
     if let Err(err) = evaluate_expression("test".to_string(), expr.to_string(), &mut env) {
+        // displaying the error marks its tokens in the program, which must not panic
+        err.to_string();
         if let SpressoErrorType::Syntax(SyntaxError { err: err_str }) = err.detail {
             assert_eq!(err_str, expected);
         } else {
@@ -108,6 +131,13 @@ pub fn check_conditional(expr: &str, expected: bool) {
     }
 }
 
+pub fn check_conditional_in_env(expr: &str, expected: bool, env: &mut Env) {
+    match eval_expr_in_env(expr, env).kind {
+        ExprKind::Atom(Atom::Bool(res)) => assert_eq!(expected, res, "{}", expr),
+        _ => panic!("Result of '{}' was not a bool.", expr),
+    }
+}
+
 pub fn eval_list_expr(expr: &str, env: &mut Env) -> Vec<Expr> {
     if let Expr {
         kind: ExprKind::List(res),
@@ -133,3 +163,19 @@ pub fn check_unit_expr_in_env(expr: &str, env: &mut Env) {
         panic!("'{}' was expected to be a unit, but was not a unit.", expr);
     }
 }
+
+/// Evaluates the expression with the given input and returns everything it printed.
+pub fn capture_output_with_input(expr: &str, input: &'static str) -> String {
+    let output = CapturedOutput::new();
+    let mut env = Env::builder()
+        .output(output.clone())
+        .input(input.as_bytes())
+        .build();
+    eval_expr_in_env(expr, &mut env);
+    output.contents()
+}
+
+/// Evaluates the expression and returns everything it printed.
+pub fn capture_output(expr: &str) -> String {
+    capture_output_with_input(expr, "")
+}
@@ -0,0 +1,133 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::collections::HashMap;
+
+use common::{
+    check_conditional_in_env, check_expr_error_in_env, check_integer_expr_in_env, eval_as,
+    eval_expr_in_env,
+};
+
+use spressolisp::{
+    convert::{FromExpr, IntoExpr},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+};
+
+fn from_expr_error<T: FromExpr + std::fmt::Debug>(expr: &str) -> String {
+    let mut env = Env::new();
+    let res = eval_expr_in_env(expr, &mut env);
+    match T::from_expr(res) {
+        Ok(val) => panic!("'{}' should not have been converted, got: {:?}", expr, val),
+        Err(err) => err.text().to_string(),
+    }
+}
+
+#[test]
+fn test_from_expr() {
+    assert_eq!(eval_as::<i64>("(+ 1 2)"), 3);
+    assert_float_relative_eq!(eval_as::<f64>("(/ 1 4)"), 0.25);
+    assert!(eval_as::<bool>("(< 1 2)"));
+    assert_eq!(eval_as::<String>("(concat \"a\" \"b\")"), "ab");
+    eval_as::<()>("()");
+    assert_eq!(eval_as::<Vec<i64>>("('(1 2 3))"), vec![1, 2, 3]);
+    assert_eq!(eval_as::<Vec<i64>>("()"), Vec::<i64>::new());
+    assert_eq!(eval_as::<Option<i64>>("()"), None);
+    assert_eq!(eval_as::<Option<i64>>("5"), Some(5));
+    assert_eq!(
+        eval_as::<(i64, String, f64)>("('(1 \"a\" 2.5))"),
+        (1, "a".to_string(), 2.5)
+    );
+
+    let map = eval_as::<HashMap<String, i64>>("('((a 1) (b 2)))");
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map["b"], 2);
+    let map = eval_as::<HashMap<String, i64>>("(json-parse \"{\\\"a\\\": 1}\")");
+    assert_eq!(map.len(), 1);
+    assert_eq!(map["a"], 1);
+}
+
+#[test]
+fn test_from_expr_errors() {
+    assert_eq!(
+        from_expr_error::<i64>("\"5\""),
+        "expected an integer, got a string: \"5\" "
+    );
+    assert_eq!(
+        from_expr_error::<i64>("1.5"),
+        "expected an integer, got a number: 1.5 "
+    );
+    assert_eq!(
+        from_expr_error::<i64>("100000000000000000000"),
+        "expected an integer that fits in 64 bits, got a number: 100000000000000000000 "
+    );
+    assert_eq!(
+        from_expr_error::<Vec<bool>>("('(true 1))"),
+        "expected a bool, got a symbol: true "
+    );
+    assert_eq!(
+        from_expr_error::<(i64, i64)>("('(1 2 3))"),
+        "expected a list of 2 elements, got a list: [ 1 2 3 ] "
+    );
+    assert_eq!(
+        from_expr_error::<HashMap<String, i64>>("('((a 1 2)))"),
+        "expected a (key value) pair, got a list: [ a 1 2 ] "
+    );
+}
+
+#[test]
+fn test_into_expr() {
+    let mut env = Env::new();
+    env.insert("n", 42i64.into_expr());
+    env.insert("names", vec!["x", "y"].into_expr());
+    env.insert("nothing", None::<i64>.into_expr());
+    env.insert("pair", (1i64, 2.5f64).into_expr());
+    let mut map = HashMap::new();
+    map.insert("b".to_string(), 2i64);
+    map.insert("a".to_string(), 1i64);
+    env.insert("map", map.into_expr());
+
+    check_integer_expr_in_env("(+ n 1)", 43, &mut env);
+    check_conditional_in_env("(== names ('(\"x\" \"y\")))", true, &mut env);
+    check_conditional_in_env("(== nothing ())", true, &mut env);
+    check_conditional_in_env("(== pair ('(1 2.5)))", true, &mut env);
+    // a map is an object, with the pairs sorted by key
+    check_conditional_in_env("(== map ('(object (\"a\" 1) (\"b\" 2))))", true, &mut env);
+}
+
+#[test]
+fn test_typed_fn() {
+    let mut env = Env::new();
+    env.register_typed_fn("repeat?", |times: i64, text: String| {
+        text.len() as i64 == times
+    });
+    env.register_typed_fn("sum", |nums: Vec<i64>| nums.iter().sum::<i64>());
+    env.register_typed_fn("answer", || 42i64);
+    env.register_typed_fn("checked-div", |lhs: i64, rhs: i64| {
+        if rhs == 0 {
+            Err(SpressoError::from(RuntimeError::from("division by zero")))
+        } else {
+            Ok(lhs / rhs)
+        }
+    });
+
+    check_conditional_in_env("(repeat? 3 \"abc\")", true, &mut env);
+    check_integer_expr_in_env("(sum ('(1 2 3)))", 6, &mut env);
+    check_integer_expr_in_env("(answer)", 42, &mut env);
+    check_integer_expr_in_env("(checked-div 7 2)", 3, &mut env);
+
+    check_expr_error_in_env(
+        "(repeat? 3)",
+        "`repeat?` needs 2 arguments, got 1",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(repeat? \"abc\" 3)",
+        "`repeat?` argument 1: expected an integer, got a string: \"abc\" ",
+        &mut env,
+    );
+    check_expr_error_in_env("(checked-div 1 0)", "division by zero", &mut env);
+}
@@ -0,0 +1,121 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::fs;
+
+use common::{
+    check_conditional, check_conditional_in_env, check_expr_error_in_env, check_string_expr_in_env,
+    eval_expr_in_env,
+};
+
+use spressolisp::{convert::IntoExpr, env::Env};
+
+#[test]
+fn test_csv_read() {
+    check_conditional(
+        r#"(== (csv-read "a,b\n1,\"x, \"\"y\"\"\"\n") ('(("a" "b") ("1" "x, \"y\""))))"#,
+        true,
+    );
+    // quoted cells can span lines, and rows can have different lengths
+    check_conditional(
+        r#"(== (csv-read "\"multi\nline\",2\n3") ('(("multi\nline" "2") ("3"))))"#,
+        true,
+    );
+    check_conditional(
+        r#"(== (csv-read "name,age\nspresso,1\nlisp,60" true) ('((object ("name" "spresso") ("age" "1")) (object ("name" "lisp") ("age" "60")))))"#,
+        true,
+    );
+}
+
+#[test]
+fn test_csv_read_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        r#"(csv-read "a,b\n1,2,3" true)"#,
+        "csv-read: CSV error: record 1 (line: 2, byte: 4): found record with 3 fields, but the previous record has 2 fields",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(csv-read "a,b" 1)"#,
+        "`csv-read` needs bools, got: 1 ",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_csv_write() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        r#"(csv-write ('(("a" "b c") (1 "x, \"y\"") ("multi\nline" ()))))"#,
+        "a,b c\n1,\"x, \"\"y\"\"\"\n\"multi\nline\",\n",
+        &mut env,
+    );
+    // rows of pairs get a header, missing columns are empty
+    check_string_expr_in_env(
+        r#"(csv-write ('((("name" "spresso") ("age" 1)) (("age" 60)))))"#,
+        "name,age\nspresso,1\n,60\n",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(csv-write ('((1 (2 3)))))"#,
+        "csv-write: a list cannot be a cell: [ 2 3 ] ",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(csv-write ('((("name" "spresso")) (("age" 60)))))"#,
+        "csv-write: column `age` is not in the header",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_csv_json_round_trip() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        r#"(csv-write (json-parse "[{\"a\": 1, \"b\": \"x\"}, {\"b\": \"y\"}]"))"#,
+        "a,b\n1,x\n,y\n",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        r#"(json-stringify (csv-read "a,b\n1,x" true))"#,
+        r#"[{"a":"1","b":"x"}]"#,
+        &mut env,
+    );
+    check_string_expr_in_env(
+        r#"(csv-write (json-parse (json-stringify (csv-read "a,b\n1,x\n2,y" true))))"#,
+        "a,b\n1,x\n2,y\n",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(csv-write ('((object ("a" 1) 2))))"#,
+        "csv-write: an object can only contain (key value) pairs with string keys",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_csv_files() {
+    let path = std::env::temp_dir().join(format!("spresso-csv-test-{}.csv", std::process::id()));
+    let mut env = Env::new();
+    env.insert("path", path.to_str().unwrap().into_expr());
+
+    eval_expr_in_env(
+        r#"(csv-write-file path (csv-read "name,age\nspresso,1" true))"#,
+        &mut env,
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "name,age\nspresso,1\n");
+    check_conditional_in_env(
+        r#"(== (csv-read-file path) ('(("name" "age") ("spresso" "1"))))"#,
+        true,
+        &mut env,
+    );
+    fs::remove_file(&path).unwrap();
+
+    check_expr_error_in_env(
+        r#"(csv-read-file "/nonexistent/spresso.csv")"#,
+        "csv-read-file: could not read `/nonexistent/spresso.csv`: No such file or directory (os error 2)",
+        &mut env,
+    );
+}
@@ -0,0 +1,24 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{check_expr_error_in_env, check_string_expr_in_env, check_unit_expr_in_env};
+
+use spressolisp::env::Env;
+
+// `setenv` is not thread-safe, so this is the only test in this file: tests in the same file
+// run on parallel threads, tests in different files run in different processes.
+#[test]
+fn test_env_vars() {
+    let mut env = Env::new();
+    check_unit_expr_in_env(r#"(getenv "SPRESSO_TEST_UNSET_VAR")"#, &mut env);
+    check_unit_expr_in_env(r#"(setenv "SPRESSO_TEST_VAR" "brewed")"#, &mut env);
+    check_string_expr_in_env(r#"(getenv "SPRESSO_TEST_VAR")"#, "brewed", &mut env);
+    assert_eq!(std::env::var("SPRESSO_TEST_VAR").unwrap(), "brewed");
+    check_expr_error_in_env(
+        r#"(setenv "A=B" "c")"#,
+        "setenv: invalid environment variable `A=B`",
+        &mut env,
+    );
+}
@@ -0,0 +1,138 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_conditional_in_env, check_expr_error_in_env, eval_expr_in_env,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_equality_of_atoms() {
+    check_conditional("(== \"a\" \"a\")", true);
+    check_conditional("(== \"a\" \"b\")", false);
+    check_conditional("(!= \"a\" \"b\")", true);
+    check_conditional("(== true true)", true);
+    check_conditional("(== true false)", false);
+    check_conditional("(== () ())", true);
+    check_conditional("(== ('red) ('red))", true);
+    check_conditional("(== ('red) ('blue))", false);
+    check_conditional("(equal? \"x\" \"x\")", true);
+}
+
+#[test]
+fn test_no_coercion_between_types() {
+    check_conditional("(== \"5\" 5)", false);
+    check_conditional("(== \"true\" true)", false);
+    check_conditional("(== () false)", false);
+    check_conditional("(!= ('a) \"a\")", true);
+}
+
+#[test]
+fn test_equality_agrees_with_ordering() {
+    let mut env = Env::new();
+    for (lhs, rhs) in [
+        ("1", "1.0"),
+        ("1/2", "0.5"),
+        ("1", "2"),
+        ("\"a\"", "\"a\""),
+        ("\"a\"", "\"b\""),
+        ("('(1 \"a\"))", "('(1.0 \"a\"))"),
+        ("('(1 2))", "('(1 2 3))"),
+    ] {
+        let equal = format!("(== {} {})", lhs, rhs);
+        let both = format!("(and (<= {} {}) (>= {} {}))", lhs, rhs, lhs, rhs);
+        assert_eq!(
+            eval_expr_in_env(&equal, &mut env),
+            eval_expr_in_env(&both, &mut env),
+            "{}",
+            equal
+        );
+    }
+
+    // neither converts a string to a number
+    check_conditional_in_env("(== \"1\" 1)", false, &mut env);
+    check_expr_error_in_env(
+        "(<= \"1\" 1)",
+        "Cannot compare a string with a number",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_numbers_compare_across_kinds() {
+    check_conditional("(== 2 2.0)", true);
+    check_conditional("(== 1/2 0.5)", true);
+    check_conditional("(!= 2 2.5)", true);
+    check_conditional("(== nan nan)", false);
+}
+
+#[test]
+fn test_deep_list_equality() {
+    check_conditional("(== ('(1 \"a\" (2 3))) ('(1.0 \"a\" (2 3))))", true);
+    check_conditional("(== ('(1 2)) ('(1 2 3)))", false);
+    check_conditional("(== ('(1 (2 3))) ('(1 (2 4))))", false);
+    check_conditional("(equal? (split \"a,b\" \",\") ('(\"a\" \"b\")))", true);
+}
+
+#[test]
+fn test_not_equal_is_variadic() {
+    check_conditional("(!= 1 1 1)", false);
+    check_conditional("(!= 1 1 2)", true);
+    check_conditional("(!= 1 2 1)", true);
+    check_conditional("(!= 1 1.0 1/1)", false);
+
+    // like `==`, the args after the first difference are not evaluated
+    let mut env = Env::new();
+    eval_expr_in_env("(define n 0)", &mut env);
+    check_conditional_in_env("(!= 1 2 (define n 1))", true, &mut env);
+    check_conditional_in_env("(== n 0)", true, &mut env);
+}
+
+#[test]
+fn test_object_equality_ignores_pair_order() {
+    check_conditional(
+        r#"(== (json-parse "{\"a\": 1, \"b\": [1, 2]}") (json-parse "{\"b\": [1, 2], \"a\": 1.0}"))"#,
+        true,
+    );
+    check_conditional(
+        r#"(== (json-parse "{\"o\": {\"x\": 1, \"y\": 2}}") (json-parse "{\"o\": {\"y\": 2, \"x\": 1}}"))"#,
+        true,
+    );
+    check_conditional(
+        r#"(!= (json-parse "{\"a\": 1, \"b\": 2}") (json-parse "{\"a\": 1, \"b\": 3}"))"#,
+        true,
+    );
+    check_conditional(
+        r#"(== (json-parse "{\"a\": 1}") (json-parse "{\"a\": 1, \"b\": 2}"))"#,
+        false,
+    );
+    // arrays keep their order
+    check_conditional("(== ('(1 2)) ('(2 1)))", false);
+    check_conditional(
+        r#"(== (json-parse "[[\"a\", 1], [\"b\", 2]]") (json-parse "[[\"b\", 2], [\"a\", 1]]"))"#,
+        false,
+    );
+}
+
+#[test]
+fn test_identity() {
+    check_conditional("(eq? 2 2)", true);
+    check_conditional("(eq? 2 2.0)", false);
+    check_conditional("(eq? ('a) ('a))", true);
+    check_conditional("(eq? \"a\" \"a\")", true);
+    check_conditional("(eq? ('(1 2)) ('(1 2)))", true);
+    check_conditional("(eq? ('(1 2)) ('(1 2.0)))", false);
+
+    let mut env = Env::new();
+    eval_expr_in_env("(define f (lambda (x) x))", &mut env);
+    eval_expr_in_env("(define g (lambda (x) x))", &mut env);
+    eval_expr_in_env("(define h f)", &mut env);
+    check_conditional_in_env("(eq? f f)", true, &mut env);
+    check_conditional_in_env("(eq? f h)", true, &mut env);
+    check_conditional_in_env("(eq? f g)", false, &mut env);
+    check_conditional_in_env("(== f g)", true, &mut env);
+    check_conditional_in_env("(eq? + +)", true, &mut env);
+}
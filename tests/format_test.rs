@@ -0,0 +1,125 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    capture_output, check_expr_error_in_env, check_string_expr_in_env, check_unit_expr_in_env,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_format_placeholders() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(format \"{} + {} = {}\" 1 2.5 (+ 1 2.5))",
+        "1 + 2.5 = 3.5",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        "(format \"{} {} {}\" \"str\" true ('(1 \"a\")))",
+        "str true [1 \"a\"]",
+        &mut env,
+    );
+    check_string_expr_in_env("(format \"{{{}}}\" 1)", "{1}", &mut env);
+}
+
+#[test]
+fn test_format_specs() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(format \"{:.2}\" 3.14159)", "3.14", &mut env);
+    check_string_expr_in_env("(format \"{:.1}\" 3)", "3.0", &mut env);
+    check_string_expr_in_env("(format \"[{:>6}]\" 42)", "[    42]", &mut env);
+    check_string_expr_in_env("(format \"[{:<6}]\" 42)", "[42    ]", &mut env);
+    check_string_expr_in_env("(format \"[{:*^7}]\" \"ab\")", "[**ab***]", &mut env);
+    check_string_expr_in_env("(format \"[{:6}]\" \"ab\")", "[ab    ]", &mut env);
+    check_string_expr_in_env("(format \"{:+06.1}\" (- 0 2.5))", "-002.5", &mut env);
+    check_string_expr_in_env("(format \"{:+}\" 7)", "+7", &mut env);
+}
+
+#[test]
+fn test_format_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(format \"{} {}\" 1)",
+        "format: not enough values for the template",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(format \"{}\" 1 2)",
+        "format: more values than placeholders in the template",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(format \"{:x}\" 1)",
+        "format: invalid format spec {:x}",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(format \"{:99999999999}\" 1)",
+        "format: 99999999999 is too large for a width or precision, the most is 65535",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(sprintf \"%.70000f\" 1)",
+        "sprintf: 70000 is too large for a width or precision, the most is 65535",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_sprintf() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(sprintf \"%d|%5.2f|%-4s|%%\" 42 3.14159 \"ab\")",
+        "42| 3.14|ab  |%",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        "(sprintf \"%04d %x %X %o\" (- 0 7) 255 255 8)",
+        "-007 ff FF 10",
+        &mut env,
+    );
+    check_string_expr_in_env("(sprintf \"%f\" 1)", "1.000000", &mut env);
+    check_expr_error_in_env(
+        "(sprintf \"%d\" 1.5)",
+        "sprintf: %d expects an integer, got: 1.5",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_printf_errors_name_printf() {
+    let mut env = Env::new();
+    check_expr_error_in_env("(printf)", "`printf` needs a template string", &mut env);
+    check_expr_error_in_env(
+        "(printf \"%d\" 1.5)",
+        "printf: %d expects an integer, got: 1.5",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(printf \"%d %d\" 1)",
+        "printf: not enough values for the template",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_display_and_printf_return_unit() {
+    let mut env = Env::new();
+    check_unit_expr_in_env("(display \"x is \" 10)", &mut env);
+    check_unit_expr_in_env("(printf \"%s\\n\" \"done\")", &mut env);
+}
+
+#[test]
+fn test_display_and_printf_output() {
+    assert_eq!(
+        capture_output("(display \"x is \" 10 \" and \" ('(1 \"a\")))"),
+        "x is 10 and [1 \"a\"]\n"
+    );
+    assert_eq!(
+        capture_output("(printf \"%s: %.1f\\n\" \"done\" 2)"),
+        "done: 2.0\n"
+    );
+}
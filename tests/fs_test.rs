@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use common::{
+    check_expr_error_in_env, check_string_expr_in_env, check_unit_expr_in_env, eval_as_in_env,
+    eval_expr_error_in_env, eval_expr_in_env,
+};
+
+use spressolisp::{convert::IntoExpr, env::Env, errors::SpressoErrorType};
+
+/// An env with `dir` bound to a new empty directory, which is removed when the test ends.
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(name: &str) -> (Self, Env) {
+        let path = std::env::temp_dir().join(format!("spresso-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        let mut env = Env::new();
+        env.insert("dir", path.to_str().unwrap().into_expr());
+        (TempDir { path }, env)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.path).ok();
+    }
+}
+
+#[test]
+fn test_read_and_write_files() {
+    let (dir, mut env) = TempDir::new("files");
+    eval_expr_in_env(r#"(define file (path-join dir "notes.txt"))"#, &mut env);
+
+    assert!(!eval_as_in_env::<bool>("(file-exists? file)", &mut env));
+    check_unit_expr_in_env(r#"(write-file file "one\n")"#, &mut env);
+    check_unit_expr_in_env(r#"(append-file file "two\r\nthree")"#, &mut env);
+    assert!(eval_as_in_env::<bool>("(file-exists? file)", &mut env));
+    check_string_expr_in_env("(read-file file)", "one\ntwo\r\nthree", &mut env);
+    assert_eq!(
+        eval_as_in_env::<Vec<String>>("(read-lines file)", &mut env),
+        vec!["one", "two", "three"]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path.join("notes.txt")).unwrap(),
+        "one\ntwo\r\nthree"
+    );
+
+    check_unit_expr_in_env("(delete-file file)", &mut env);
+    assert!(!eval_as_in_env::<bool>("(file-exists? file)", &mut env));
+}
+
+#[test]
+fn test_directories() {
+    let (_dir, mut env) = TempDir::new("dirs");
+    check_unit_expr_in_env(r#"(make-dir (path-join dir "b" "nested"))"#, &mut env);
+    eval_expr_in_env(r#"(write-file (path-join dir "a.txt") "")"#, &mut env);
+    assert_eq!(
+        eval_as_in_env::<Vec<String>>("(list-dir dir)", &mut env),
+        vec!["a.txt", "b"]
+    );
+    assert_eq!(
+        eval_as_in_env::<Vec<String>>(r#"(list-dir (path-join dir "b"))"#, &mut env),
+        vec!["nested"]
+    );
+}
+
+#[test]
+fn test_path_helpers() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        r#"(path-join "out" "reports" "march.csv")"#,
+        "out/reports/march.csv",
+        &mut env,
+    );
+    check_string_expr_in_env(r#"(path-join "out" "/abs")"#, "/abs", &mut env);
+    check_string_expr_in_env(
+        r#"(path-basename "out/reports/march.csv")"#,
+        "march.csv",
+        &mut env,
+    );
+    check_string_expr_in_env(r#"(path-extension "out/march.csv")"#, "csv", &mut env);
+    check_unit_expr_in_env(r#"(path-extension "out/README")"#, &mut env);
+    check_unit_expr_in_env(r#"(path-basename "/")"#, &mut env);
+}
+
+#[test]
+fn test_io_errors() {
+    let mut env = Env::new();
+    let err = eval_expr_error_in_env(r#"(read-file "/nonexistent/spresso.txt")"#, &mut env);
+    assert_eq!(err.name(), "IO Error");
+    assert_eq!(
+        err.text(),
+        "read-file: could not read `/nonexistent/spresso.txt`: No such file or directory (os error 2)"
+    );
+    match err.detail {
+        SpressoErrorType::Io(err) => {
+            assert_eq!(err.kind, ErrorKind::NotFound);
+            assert_eq!(err.os_code, Some(2));
+        }
+        _ => panic!("expected an IO error"),
+    }
+
+    check_expr_error_in_env(
+        r#"(list-dir "/nonexistent")"#,
+        "list-dir: could not list `/nonexistent`: No such file or directory (os error 2)",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(write-file "/nonexistent/a.txt" 1)"#,
+        "Expected a string, got: 1 ",
+        &mut env,
+    );
+}
@@ -0,0 +1,72 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    capture_output, capture_output_with_input, check_expr_error_in_env, eval_expr_in_env,
+};
+
+use spressolisp::env::{CapturedOutput, Env};
+
+#[test]
+fn test_capture_printed_output() {
+    assert_eq!(capture_output("(print 1 \"a\")"), "1 \n\"a\" \n");
+    assert_eq!(capture_output("(display \"x is \" 10)"), "x is 10\n");
+    assert_eq!(capture_output("(printf \"%d-%s\" 1 \"a\")"), "1-a");
+    assert_eq!(capture_output("(+ 1 2)"), "");
+}
+
+#[test]
+fn test_input_from_stream() {
+    assert_eq!(
+        capture_output_with_input("(display \"hello \" (input \"name?\"))", "world\nignored\n"),
+        "\"name?\" \nhello world\n"
+    );
+
+    let mut env = Env::builder().input(&b"first\nsecond\n"[..]).build();
+    eval_expr_in_env("(define a (input))", &mut env);
+    eval_expr_in_env("(define b (input))", &mut env);
+    eval_expr_in_env("(define c (input))", &mut env);
+    assert_eq!(env["a"].to_plain_string(), "first");
+    assert_eq!(env["b"].to_plain_string(), "second");
+    // nothing left to read
+    assert_eq!(env["c"].to_plain_string(), "");
+}
+
+#[test]
+fn test_output_is_shared_between_clones() {
+    let output = CapturedOutput::new();
+    let mut env = Env::builder().output(output.clone()).build();
+    eval_expr_in_env("(display 1)", &mut env);
+    assert_eq!(output.contents(), "1\n");
+    eval_expr_in_env("(display 2)", &mut env);
+    assert_eq!(output.contents(), "1\n2\n");
+}
+
+#[test]
+fn test_builder_options() {
+    let mut env = Env::builder()
+        .strict_arithmetic()
+        .disable_string_coercion()
+        .build();
+    assert!(env.is_strict_arithmetic());
+    assert!(!env.is_string_coercion_enabled());
+    check_expr_error_in_env("(* 9223372036854775807 2)", "Integer overflow", &mut env);
+
+    let mut first = Env::builder().random_seed(7).build();
+    let mut second = Env::builder().random_seed(7).build();
+    assert_eq!(
+        eval_expr_in_env("(random-int 1000000)", &mut first),
+        eval_expr_in_env("(random-int 1000000)", &mut second)
+    );
+}
+
+#[test]
+fn test_display_env_to_output() {
+    let output = CapturedOutput::new();
+    let mut env = Env::builder().output(output.clone()).build();
+    eval_expr_in_env("(define answer 42)", &mut env);
+    assert!(env.display().is_ok());
+    assert!(output.contents().contains("answer\t:\t42 "));
+}
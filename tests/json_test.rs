@@ -0,0 +1,153 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_conditional_in_env, check_expr_error_in_env, check_float_expr,
+    check_integer_expr_in_env, check_string_expr_in_env, eval_expr_in_env,
+};
+
+use spressolisp::{convert::IntoExpr, env::Env};
+
+#[test]
+fn test_json_parse() {
+    check_conditional(
+        r#"(== (json-parse "{\"name\": \"spresso\", \"tags\": [\"a\", \"b\"]}") ('(object ("name" "spresso") ("tags" ("a" "b")))))"#,
+        true,
+    );
+    check_conditional(r#"(== (json-parse " [1, -2, null] ") ('(1 -2 ())))"#, true);
+    check_conditional(r#"(json-parse "true")"#, true);
+    check_conditional(
+        r#"(== (json-parse "\"\\u00e9\\n\\ud83d\\ude00\"") "é\n😀")"#,
+        true,
+    );
+    check_float_expr(r#"(json-parse "-1.5e2")"#, -150.0);
+
+    let mut env = Env::new();
+    check_integer_expr_in_env(r#"(json-parse "42")"#, 42, &mut env);
+    check_string_expr_in_env(
+        r#"(number->string (json-parse "123456789012345678901234567890"))"#,
+        "123456789012345678901234567890",
+        &mut env,
+    );
+    check_conditional(r#"(== (json-parse "{}") ('(object)))"#, true);
+}
+
+#[test]
+fn test_json_parse_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        r#"(json-parse "{\"a\": 1,\n \"b\" 2}")"#,
+        "json-parse: expected `:`, found `2` at line 2, column 6",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(json-parse "[1, 2")"#,
+        "json-parse: expected `,` or `]` after a value in an array at line 1, column 6",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(json-parse "[01]")"#,
+        "json-parse: numbers cannot have leading zeros at line 1, column 4",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(json-parse "nope")"#,
+        "json-parse: unexpected `nope` at line 1, column 5",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(json-parse "1 2")"#,
+        "json-parse: unexpected `2` after the value at line 1, column 3",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_json_parse_nesting_limit() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        r#"(json-parse (repeat "[" 200000))"#,
+        "json-parse: arrays and objects are nested deeper than 128 levels at line 1, column 129",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(json-parse (repeat "{\"a\":" 129))"#,
+        "json-parse: arrays and objects are nested deeper than 128 levels at line 1, column 641",
+        &mut env,
+    );
+    // 128 levels are fine
+    check_integer_expr_in_env(
+        r#"(string-length (json-stringify (json-parse (concat (repeat "[" 128) (repeat "]" 128)))))"#,
+        256,
+        &mut env,
+    );
+}
+
+#[test]
+fn test_json_stringify() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        r#"(json-stringify ('(object ("a" 1) ("b" (1.5 "x\"y" ())))))"#,
+        r#"{"a":1,"b":[1.5,"x\"y",null]}"#,
+        &mut env,
+    );
+    check_string_expr_in_env(r#"(json-stringify (/ 1 4))"#, "0.25", &mut env);
+    check_string_expr_in_env(r#"(json-stringify 2.0)"#, "2.0", &mut env);
+    check_string_expr_in_env(r#"(json-stringify (< 1 2))"#, "true", &mut env);
+    // only lists tagged with `object` are objects
+    check_string_expr_in_env(
+        r#"(json-stringify ('(("a" 1) ("b" 2))))"#,
+        r#"[["a",1],["b",2]]"#,
+        &mut env,
+    );
+    check_string_expr_in_env(r#"(json-stringify ('(object)))"#, "{}", &mut env);
+    check_string_expr_in_env(
+        r#"(json-stringify ('(object ("a" 1) ("b" (1 2)))) true)"#,
+        "{\n  \"a\": 1,\n  \"b\": [\n    1,\n    2\n  ]\n}",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_json_stringify_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(json-stringify (lambda x x))",
+        "json-stringify: a function cannot be represented in JSON",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(json-stringify ('(1 nan)))",
+        "json-stringify: NaN cannot be represented in JSON",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(json-stringify ('(object (1 2))))",
+        "json-stringify: an object can only contain (key value) pairs with string keys",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let mut env = Env::new();
+    eval_expr_in_env(
+        r#"(define doc "{\"name\":\"spresso\",\"version\":[0,1],\"beta\":false,\"extra\":null}")"#,
+        &mut env,
+    );
+    check_conditional_in_env("(== (json-stringify (json-parse doc)) doc)", true, &mut env);
+
+    // arrays of pairs stay arrays and empty objects stay objects
+    for doc in [
+        r#"[["a",1],["b",2]]"#,
+        r#"{"pairs":[["a",[["b",{}]]]]}"#,
+        "{}",
+        r#"{"a":{},"b":[]}"#,
+        r#"[{},[]]"#,
+    ] {
+        env.insert("doc", doc.into_expr());
+        check_string_expr_in_env("(json-stringify (json-parse doc))", doc, &mut env);
+    }
+}
@@ -0,0 +1,67 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{check_conditional, check_expr_error_in_env};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_variadic_and_or() {
+    check_conditional("(and true true true)", true);
+    check_conditional("(and true false true)", false);
+    check_conditional("(and)", true);
+    check_conditional("(and (> 3 2))", true);
+    check_conditional("(or false false true)", true);
+    check_conditional("(or false false false)", false);
+    check_conditional("(or)", false);
+}
+
+#[test]
+fn test_short_circuit() {
+    // the rest of the args would fail if they were evaluated
+    check_conditional("(and false (undefined-function))", false);
+    check_conditional("(or true (/ 1 0))", true);
+    check_conditional("(and true false 5)", false);
+}
+
+#[test]
+fn test_chained_comparisons() {
+    check_conditional("(< 1 2 3)", true);
+    check_conditional("(< 1 3 2)", false);
+    check_conditional("(<= 1 1 2)", true);
+    check_conditional("(> 3 2 1)", true);
+    check_conditional("(>= 3 3 4)", false);
+    check_conditional("(== 1 1 1.0)", true);
+    check_conditional("(== 1 1 2)", false);
+    check_conditional("(< \"a\" \"b\" \"c\")", true);
+
+    // stops at the first pair which does not hold
+    check_conditional("(< 3 2 (undefined-function))", false);
+}
+
+#[test]
+fn test_logical_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env("(and true 1)", "`and` needs bools, got: 1 ", &mut env);
+    check_expr_error_in_env(
+        "(or false \"yes\")",
+        "`or` needs bools, got: \"yes\" ",
+        &mut env,
+    );
+    check_expr_error_in_env("(not 1)", "`not` needs bools, got: 1 ", &mut env);
+    check_expr_error_in_env(
+        "(not true false)",
+        "`not` needs 1 arguments, got 2",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_relational_arity_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env("(< 1)", "`<` needs at least 2 arguments, got 1", &mut env);
+    check_expr_error_in_env("(== 1)", "`==` needs at least 2 arguments, got 1", &mut env);
+    check_expr_error_in_env("(!= 1)", "`!=` needs at least 2 arguments, got 1", &mut env);
+}
@@ -0,0 +1,167 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_expr_error_in_env, check_float_expr, check_integer_expr, check_integer_expr_in_env,
+    check_string_expr_in_env,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_mod_rem_quot() {
+    check_integer_expr("(mod 7 3)", 1);
+    check_integer_expr("(mod (- 0 7) 3)", 2);
+    check_integer_expr("(mod 7 (- 0 3))", -2);
+    check_integer_expr("(rem (- 0 7) 3)", -1);
+    check_integer_expr("(rem 7 (- 0 3))", 1);
+    check_integer_expr("(quot 7 2)", 3);
+    check_integer_expr("(quot (- 0 7) 2)", -3);
+    check_float_expr("(mod (- 0 7.5) 2)", 0.5);
+    check_float_expr("(rem 7.5 2)", 1.5);
+    check_float_expr("(quot 7.5 2)", 3.0);
+
+    let mut env = Env::new();
+    check_string_expr_in_env("(number->string (mod 7/2 1))", "1/2", &mut env);
+    check_expr_error_in_env("(mod 1 0)", "Division By Zero", &mut env);
+    check_expr_error_in_env("(quot 1 0.0)", "Division By Zero", &mut env);
+}
+
+#[test]
+fn test_expt() {
+    check_integer_expr("(expt 2 10)", 1024);
+    check_integer_expr("(expt 7 0)", 1);
+    check_float_expr("(expt 2 0.5)", 2.0_f64.sqrt());
+    check_float_expr("(expt 2.5 2)", 6.25);
+
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string (expt 2 100))",
+        "1267650600228229401496703205376",
+        &mut env,
+    );
+    check_string_expr_in_env("(number->string (expt 2 (- 0 2)))", "1/4", &mut env);
+    check_string_expr_in_env("(number->string (expt 2/3 2))", "4/9", &mut env);
+    check_expr_error_in_env("(expt 0 (- 0 1))", "Division By Zero", &mut env);
+    check_expr_error_in_env(
+        "(expt 2 100000000000)",
+        "expt: exponent 100000000000 is too large",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(expt 3 2000000000)",
+        "expt: the result would have more than 4194304 bits",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(expt 2/3 (- 0 2000000000))",
+        "expt: the result would have more than 4194304 bits",
+        &mut env,
+    );
+    check_integer_expr("(expt 1 2000000000)", 1);
+    check_integer_expr("(expt (- 0 1) 2000000001)", -1);
+}
+
+#[test]
+fn test_sqrt_exp_log() {
+    check_integer_expr("(sqrt 16)", 4);
+    check_float_expr("(sqrt 2)", 2.0_f64.sqrt());
+    check_float_expr("(sqrt 2.25)", 1.5);
+    check_float_expr("(exp 0)", 1.0);
+    check_float_expr("(log e)", 1.0);
+    check_float_expr("(log 100 10)", 2.0);
+
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(sqrt (- 0 4))",
+        "sqrt: expected a non-negative number, got: -4",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(log 0)",
+        "log: expected a positive number, got: 0",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_trig() {
+    check_float_expr("(sin (/ pi 2))", 1.0);
+    check_float_expr("(cos pi)", -1.0);
+    check_float_expr("(tan 0)", 0.0);
+    check_float_expr("(asin 1)", std::f64::consts::FRAC_PI_2);
+    check_float_expr("(acos 1)", 0.0);
+    check_float_expr("(atan 1)", std::f64::consts::FRAC_PI_4);
+    check_float_expr("(atan 1 (- 0 1))", 3.0 * std::f64::consts::FRAC_PI_4);
+
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(asin 2)",
+        "asin: expected a number between -1 and 1, got: 2",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_rounding() {
+    check_integer_expr("(floor 7/2)", 3);
+    check_integer_expr("(ceil 7/2)", 4);
+    check_integer_expr("(round 7/2)", 4);
+    check_integer_expr("(truncate (- 0 7/2))", -3);
+    check_integer_expr("(floor (- 0 7/2))", -4);
+    check_integer_expr("(round 5)", 5);
+    check_float_expr("(floor 2.7)", 2.0);
+    check_float_expr("(ceil 2.1)", 3.0);
+    check_float_expr("(round 2.5)", 3.0);
+    check_float_expr("(truncate (- 0 2.7))", -2.0);
+}
+
+#[test]
+fn test_abs_min_max() {
+    check_integer_expr("(abs (- 2 5))", 3);
+    check_float_expr("(abs (- 0 2.5))", 2.5);
+    check_integer_expr("(min 3 1 2)", 1);
+    check_integer_expr("(max 3 1 2)", 3);
+    check_float_expr("(max 1 2.5)", 2.5);
+    check_integer_expr("(min 5)", 5);
+
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string (abs (- (- 0 9223372036854775807) 1)))",
+        "9223372036854775808",
+        &mut env,
+    );
+    check_expr_error_in_env("(min)", "`min` needs at least 1 argument, got 0", &mut env);
+}
+
+#[test]
+fn test_gcd_lcm() {
+    check_integer_expr("(gcd 12 18)", 6);
+    check_integer_expr("(gcd 12 18 8)", 2);
+    check_integer_expr("(gcd)", 0);
+    check_integer_expr("(lcm 4 6)", 12);
+    check_integer_expr("(lcm)", 1);
+
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(gcd 4 2.5)",
+        "gcd: expected an integer, got: 2.5",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_strict_math_overflow() {
+    let mut env = Env::new();
+    env.enable_strict_arithmetic();
+    check_expr_error_in_env("(expt 2 64)", "Integer overflow", &mut env);
+    check_expr_error_in_env("(expt 3 2000000000)", "Integer overflow", &mut env);
+    check_integer_expr_in_env("(expt (- 0 2) 63)", -9223372036854775808, &mut env);
+    check_expr_error_in_env(
+        "(abs (- (- 0 9223372036854775807) 1))",
+        "Integer overflow",
+        &mut env,
+    );
+}
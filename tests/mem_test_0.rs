@@ -21,38 +21,41 @@ fn test() {
 
     let stats = dhat::HeapStats::get();
 
+    // an empty program allocates little more than the builtins registered by `Env::new`, so
+    // these grow with every builtin added
+
     // allocations done in total
     dhat::assert!(
-        matches!(stats.total_blocks, 30..=40),
+        matches!(stats.total_blocks, 130..=150),
         "{} not in range",
         stats.total_blocks
     );
     dhat::assert!(
-        matches!(stats.total_bytes, 10_000..=10_500),
+        matches!(stats.total_bytes, 75_000..=83_000),
         "{} not in range",
         stats.total_bytes
     );
 
     // peak of heap size
     dhat::assert!(
-        matches!(stats.max_blocks, 15..=20),
+        matches!(stats.max_blocks, 105..=125),
         "{} not in range",
         stats.max_blocks
     );
     dhat::assert!(
-        matches!(stats.max_bytes, 7_000..=8_000),
+        matches!(stats.max_bytes, 56_000..=63_000),
         "{} not in range",
         stats.max_bytes
     );
 
     // allocations remaining at this point
     dhat::assert!(
-        matches!(stats.curr_blocks, 20..=30),
+        matches!(stats.curr_blocks, 120..=140),
         "{} not in range",
         stats.curr_blocks
     );
     dhat::assert!(
-        matches!(stats.curr_bytes, 5_000..=6_000),
+        matches!(stats.curr_bytes, 38_000..=43_000),
         "{} not in range",
         stats.curr_bytes
     );
@@ -29,7 +29,7 @@ fn test() {
         stats.total_blocks
     );
     dhat::assert!(
-        matches!(stats.total_bytes, 7_650_000..=7_750_000),
+        matches!(stats.total_bytes, 8_150_000..=8_250_000),
         "{} not in range",
         stats.total_bytes
     );
@@ -41,7 +41,7 @@ fn test() {
         stats.max_blocks
     );
     dhat::assert!(
-        matches!(stats.max_bytes, 1_900_000..=2_000_000),
+        matches!(stats.max_bytes, 2_050_000..=2_150_000),
         "{} not in range",
         stats.max_bytes
     );
@@ -53,7 +53,7 @@ fn test() {
         stats.curr_blocks
     );
     dhat::assert!(
-        matches!(stats.curr_bytes, 1_850_000..=1_950_000),
+        matches!(stats.curr_bytes, 2_000_000..=2_100_000),
         "{} not in range",
         stats.curr_bytes
     );
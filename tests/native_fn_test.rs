@@ -0,0 +1,83 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::{cell::Cell, rc::Rc};
+
+use common::{check_expr_error_in_env, check_integer_expr_in_env, eval_expr_in_env};
+
+use spressolisp::{
+    ast::{Atom, Expr, ExprKind, Number},
+    env::Env,
+    errors::{RuntimeError, SpressoError},
+    eval::{execute_single, extract_num},
+};
+
+fn register_counter(env: &mut Env) -> Rc<Cell<i64>> {
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    env.register_fn("next-id", move |_, _| {
+        counter.set(counter.get() + 1);
+        Ok(ExprKind::Atom(Atom::Number(Number::Int(counter.get()))).into())
+    });
+    count
+}
+
+#[test]
+fn test_native_fn_captures_state() {
+    let mut env = Env::new();
+    let count = register_counter(&mut env);
+
+    check_integer_expr_in_env("(next-id)", 1, &mut env);
+    check_integer_expr_in_env("(next-id)", 2, &mut env);
+    check_integer_expr_in_env("(+ (next-id) (next-id))", 7, &mut env);
+    assert_eq!(count.get(), 4);
+}
+
+#[test]
+fn test_native_fn_args() {
+    let mut env = Env::new();
+    let offset = 100;
+    env.register_fn("add-offset", move |args: Vec<Expr>, env: &mut Env| {
+        if args.len() != 1 {
+            return Err(SpressoError::from(RuntimeError::from(
+                "add-offset needs exactly one argument",
+            )));
+        }
+        let arg = execute_single(args[0].clone(), env)?;
+        let num = extract_num(arg, env)?;
+        Ok(ExprKind::Atom(Atom::Number((num + Number::Int(offset))?)).into())
+    });
+
+    check_integer_expr_in_env("(add-offset 5)", 105, &mut env);
+    check_integer_expr_in_env("(add-offset (* 2 3))", 106, &mut env);
+    eval_expr_in_env("(define x 1)", &mut env);
+    check_integer_expr_in_env("(add-offset x)", 101, &mut env);
+    check_expr_error_in_env(
+        "(add-offset 1 2)",
+        "add-offset needs exactly one argument",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_native_fn_as_value() {
+    let mut env = Env::new();
+    register_counter(&mut env);
+
+    eval_expr_in_env("(define f next-id)", &mut env);
+    check_integer_expr_in_env("(f)", 1, &mut env);
+
+    let res = eval_expr_in_env("(== f next-id)", &mut env);
+    assert_eq!(res.kind, ExprKind::Atom(Atom::Bool(true)));
+
+    // a different closure is never equal, even with the same code
+    register_counter(&mut env);
+    let res = eval_expr_in_env("(== f next-id)", &mut env);
+    assert_eq!(res.kind, ExprKind::Atom(Atom::Bool(false)));
+
+    let res = eval_expr_in_env("f", &mut env);
+    assert_eq!(format!("{:?}", res.kind), "NativeFunc");
+    assert_eq!(res.to_string(), "built-in function ");
+}
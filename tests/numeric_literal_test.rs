@@ -0,0 +1,117 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_float_expr, check_integer_expr, check_number_syntax_err,
+    check_string_expr_in_env, eval_expr_in_env,
+};
+
+use spressolisp::{
+    ast::{Atom, ExprKind, Number},
+    env::Env,
+};
+
+#[test]
+fn test_signed_literals() {
+    check_integer_expr("-5", -5);
+    check_integer_expr("+5", 5);
+    check_integer_expr("(- 3 -5)", 8);
+    check_integer_expr("(+ -2 -3)", -5);
+    check_float_expr("-2.5", -2.5);
+    check_float_expr("-.5", -0.5);
+    check_integer_expr("(* -7/2 2)", -7);
+    check_integer_expr("-9223372036854775808", i64::MIN);
+
+    // a sign on its own is still a symbol
+    check_integer_expr("(- 10 4)", 6);
+    check_integer_expr("(+ 1 2)", 3);
+}
+
+#[test]
+fn test_exponents() {
+    check_float_expr("1e3", 1000.0);
+    check_float_expr("1.5e-3", 0.0015);
+    check_float_expr("2E+2", 200.0);
+    check_float_expr("-1e2", -100.0);
+}
+
+#[test]
+fn test_radix_prefixes() {
+    check_integer_expr("0xff", 255);
+    check_integer_expr("0xFF", 255);
+    check_integer_expr("0b1010", 10);
+    check_integer_expr("0o17", 15);
+    check_integer_expr("-0x10", -16);
+
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        "(number->string 0xffffffffffffffffff)",
+        "4722366482869645213695",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_separators() {
+    check_integer_expr("1_000_000", 1_000_000);
+    check_integer_expr("0xff_ff", 0xffff);
+    check_float_expr("1_000.000_5", 1000.0005);
+}
+
+#[test]
+fn test_inf_and_nan() {
+    check_conditional("(> inf 1000000000000000000000000)", true);
+    check_conditional("(< -inf 0)", true);
+    check_float_expr("(+ inf 1)", f64::INFINITY);
+
+    let mut env = Env::new();
+    match eval_expr_in_env("nan", &mut env).kind {
+        ExprKind::Atom(Atom::Number(Number::Float(num))) => assert!(num.is_nan()),
+        _ => panic!("nan is not a float"),
+    }
+}
+
+#[test]
+fn test_malformed_numbers() {
+    check_number_syntax_err(
+        "1.2.3",
+        "Invalid number literal `1.2.3`: more than one decimal point",
+    );
+    check_number_syntax_err(
+        "12abc",
+        "Invalid number literal `12abc`: unexpected character `a`",
+    );
+    check_number_syntax_err(
+        "1e",
+        "Invalid number literal `1e`: missing digits in the exponent",
+    );
+    check_number_syntax_err(
+        "0x",
+        "Invalid number literal `0x`: missing digits after `0x`",
+    );
+    check_number_syntax_err(
+        "0b102",
+        "Invalid number literal `0b102`: unexpected character `2`",
+    );
+    check_number_syntax_err(
+        "1__000",
+        "Invalid number literal `1__000`: `_` is only allowed between digits",
+    );
+    check_number_syntax_err(
+        "1_",
+        "Invalid number literal `1_`: `_` is only allowed between digits",
+    );
+    check_number_syntax_err("-.", "Invalid number literal `-.`: missing digits");
+    check_number_syntax_err(
+        "(+ 1 2x)",
+        "Invalid number literal `2x`: unexpected character `x`",
+    );
+}
+
+#[test]
+fn test_string_to_number_uses_literal_syntax() {
+    check_integer_expr("(string->number \"0x1f\")", 31);
+    check_integer_expr("(number \"-1_000\")", -1000);
+}
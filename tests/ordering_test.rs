@@ -0,0 +1,100 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_expr_error_in_env, check_integer_expr_in_env, eval_expr_in_env,
+};
+
+use spressolisp::{
+    ast::{Atom, ExprKind},
+    env::Env,
+};
+
+#[test]
+fn test_string_ordering() {
+    check_conditional("(< \"apple\" \"banana\")", true);
+    check_conditional("(> \"apple\" \"banana\")", false);
+    check_conditional("(< \"10\" \"9\")", true);
+    check_conditional("(<= \"abc\" \"abc\")", true);
+    check_conditional("(>= \"abd\" \"abc\")", true);
+    check_conditional("(< \"ab\" \"abc\")", true);
+    check_conditional("(< ('a) ('b))", true);
+}
+
+#[test]
+fn test_list_ordering() {
+    check_conditional("(< ('(1 2 3)) ('(1 2 4)))", true);
+    check_conditional("(> ('(1 2 3)) ('(1 2)))", true);
+    check_conditional("(<= ('(1 2)) ('(1 2)))", true);
+    check_conditional("(< ('(\"a\" 2)) ('(\"b\" 1)))", true);
+    check_conditional("(< ('((1 2) 3)) ('((1 3) 0)))", true);
+    check_conditional("(< ('(1 2.5)) ('(1 5/2)))", false);
+}
+
+#[test]
+fn test_mixed_types_are_errors() {
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(< \"a\" ('(1 2)))",
+        "Cannot compare a string with a list",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(> true false)",
+        "Cannot compare bool values, they are unordered",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(< ('(1 \"a\")) ('(1 ())))",
+        "Cannot compare a string with a unit",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(< \"10\" 9)",
+        "Cannot compare a string with a number",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_string_coercion_opt_out() {
+    let mut env = Env::new();
+    env.disable_string_coercion();
+    check_expr_error_in_env(
+        "(+ \"1\" 2)",
+        "Expected a number, got a string: \"1\" (implicit conversion of strings to numbers is disabled)",
+        &mut env,
+    );
+
+    // explicit conversions still work
+    check_integer_expr_in_env("(+ (number \"1\") 2)", 3, &mut env);
+    match eval_expr_in_env("(< \"10\" \"9\")", &mut env).kind {
+        ExprKind::Atom(Atom::Bool(res)) => assert!(res),
+        _ => panic!("Result was not a bool."),
+    }
+}
+
+#[test]
+fn test_number_does_not_evaluate_twice() {
+    let mut env = Env::new();
+    eval_expr_in_env("(define l (quote (+ 1 2)))", &mut env);
+    check_expr_error_in_env(
+        "(number l)",
+        "Cannot cast a list into a number: [ + 1 2 ] ",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(number (quote y))",
+        "Cannot cast a symbol into a number: y ",
+        &mut env,
+    );
+    check_integer_expr_in_env("(number (+ 1 2))", 3, &mut env);
+}
+
+#[test]
+fn test_nan_is_not_ordered() {
+    check_conditional("(< nan 1)", false);
+    check_conditional("(>= nan 1)", false);
+}
@@ -0,0 +1,106 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::fs;
+use std::process::Command;
+
+use common::{eval_expr_error_in_env, eval_expr_in_env};
+
+use spressolisp::{
+    ast::Expr,
+    convert::{FromExpr, IntoExpr},
+    env::Env,
+};
+
+#[test]
+fn test_command_line_args() {
+    let mut env = Env::new();
+    assert_eq!(
+        eval_expr_in_env("command-line-args", &mut env),
+        Vec::<String>::new().into_expr()
+    );
+    env.set_command_line_args(vec!["-v".to_string(), "in.txt".to_string()]);
+    assert_eq!(
+        eval_expr_in_env("command-line-args", &mut env),
+        vec!["-v", "in.txt"].into_expr()
+    );
+}
+
+#[test]
+fn test_exit() {
+    let mut env = Env::new();
+    let err = eval_expr_error_in_env("(define a 1) (exit 3) (define a 2)", &mut env);
+    assert_eq!(err.exit_code(), Some(3));
+    assert_eq!(err.text(), "exited with status code 3");
+    // nothing after exit runs, even inside a lambda
+    assert_eq!(env["a"], 1i64.into_expr());
+    let err = eval_expr_error_in_env("((lambda x (exit)) 1)", &mut env);
+    assert_eq!(err.exit_code(), Some(0));
+
+    let err = eval_expr_error_in_env("(+ 1 x)", &mut env);
+    assert_eq!(err.exit_code(), None);
+    for code in ["256", "(- 0 1)", "10000000000"] {
+        let err = eval_expr_error_in_env(&format!("(exit {})", code), &mut env);
+        assert_eq!(err.exit_code(), None);
+        assert!(
+            err.text().ends_with("is not between 0 and 255"),
+            "{}",
+            err.text()
+        );
+    }
+    let err = eval_expr_error_in_env("(exit 255)", &mut env);
+    assert_eq!(err.exit_code(), Some(255));
+}
+
+#[test]
+fn test_run_process() {
+    let mut env = Env::new();
+    let res = eval_expr_in_env(
+        r#"(run-process "sh" "-c" "echo out; echo err >&2; exit 3")"#,
+        &mut env,
+    );
+    let res = match <Vec<(String, Expr)>>::from_expr(res) {
+        Ok(res) => res,
+        Err(err) => panic!("run-process did not return pairs: {}", err),
+    };
+    assert_eq!(
+        res,
+        vec![
+            ("stdout".to_string(), "out\n".into_expr()),
+            ("stderr".to_string(), "err\n".into_expr()),
+            ("status".to_string(), 3i64.into_expr()),
+        ]
+    );
+
+    let err = eval_expr_error_in_env(r#"(run-process "spresso-no-such-program")"#, &mut env);
+    assert_eq!(err.name(), "IO Error");
+    assert_eq!(
+        err.text(),
+        "run-process: could not run `spresso-no-such-program`: No such file or directory (os error 2)"
+    );
+}
+
+#[test]
+fn test_script_status_code_and_args() {
+    let script = std::env::temp_dir().join(format!("spresso-exit-{}.lisp", std::process::id()));
+    fs::write(
+        &script,
+        "(display command-line-args)\n(exit (string-length (concat \"ab\" \"c\")))",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_spressolisp"))
+        .arg(&script)
+        .args(["one", "two"])
+        .output()
+        .unwrap();
+    fs::remove_file(&script).unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "[\"one\" \"two\"]\n"
+    );
+    assert_eq!(output.status.code(), Some(3));
+}
@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{check_expr_error_in_env, eval_expr_in_env, eval_list_expr};
+
+use spressolisp::{
+    ast::{Atom, ExprKind, Number},
+    env::Env,
+};
+
+fn eval_int(expr: &str, env: &mut Env) -> i64 {
+    match eval_expr_in_env(expr, env).kind {
+        ExprKind::Atom(Atom::Number(Number::Int(num))) => num,
+        _ => panic!("Result of '{}' was not an integer.", expr),
+    }
+}
+
+fn eval_float(expr: &str, env: &mut Env) -> f64 {
+    match eval_expr_in_env(expr, env).kind {
+        ExprKind::Atom(Atom::Number(Number::Float(num))) => num,
+        _ => panic!("Result of '{}' was not a float.", expr),
+    }
+}
+
+#[test]
+fn test_seeding_is_reproducible() {
+    let mut first = Env::new();
+    let mut second = Env::new();
+    eval_expr_in_env("(random-seed 42)", &mut first);
+    eval_expr_in_env("(random-seed 42)", &mut second);
+
+    for _ in 0..10 {
+        assert_eq!(
+            eval_int("(random-int 1000000)", &mut first),
+            eval_int("(random-int 1000000)", &mut second)
+        );
+    }
+    assert_eq!(
+        eval_list_expr("(shuffle ('(1 2 3 4 5 6 7 8)))", &mut first),
+        eval_list_expr("(shuffle ('(1 2 3 4 5 6 7 8)))", &mut second)
+    );
+
+    // seeding again starts the sequence over
+    eval_expr_in_env("(random-seed 42)", &mut first);
+    let a = eval_float("(random-float)", &mut first);
+    eval_expr_in_env("(random-seed 42)", &mut first);
+    assert_eq!(a, eval_float("(random-float)", &mut first));
+}
+
+#[test]
+fn test_envs_are_independent() {
+    let mut first = Env::new();
+    let mut second = Env::new();
+    first.set_random_seed(7);
+    second.set_random_seed(7);
+
+    // using one env does not advance the other
+    eval_int("(random-int 100)", &mut first);
+    eval_int("(random-int 100)", &mut first);
+    let expected = eval_int("(random-int 1000000)", &mut first);
+
+    let mut third = Env::new();
+    third.set_random_seed(7);
+    eval_int("(random-int 100)", &mut third);
+    eval_int("(random-int 100)", &mut third);
+    assert_eq!(expected, eval_int("(random-int 1000000)", &mut third));
+    assert_ne!(
+        eval_int("(random-int 1000000)", &mut second),
+        eval_int("(random-int 1000000)", &mut first)
+    );
+}
+
+#[test]
+fn test_ranges() {
+    let mut env = Env::new();
+    for _ in 0..200 {
+        let num = eval_int("(random-int 1 7)", &mut env);
+        assert!((1..7).contains(&num));
+        let num = eval_int("(random-int -3 3)", &mut env);
+        assert!((-3..3).contains(&num));
+        let num = eval_float("(random-float)", &mut env);
+        assert!((0.0..1.0).contains(&num));
+        let num = eval_float("(random-float 1.5 2.5)", &mut env);
+        assert!((1.5..2.5).contains(&num));
+        let num = eval_float("(random-float -1e308 1e308)", &mut env);
+        assert!(num.is_finite() && (-1e308..1e308).contains(&num));
+        // no float lies between the bounds, so the result can only be the lower one
+        let num = eval_float("(random-float 1.0 1.0000000000000002)", &mut env);
+        assert_eq!(num, 1.0);
+    }
+
+    check_expr_error_in_env(
+        "(random-int 5 5)",
+        "random-int: the range is empty, the upper bound must be greater than the lower bound",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        "(random-int 1.5)",
+        "random-int: expected an integer, got: 1.5",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_shuffle_and_choice() {
+    let mut env = Env::new();
+    let mut shuffled = eval_list_expr("(shuffle ('(1 2 3 4 5)))", &mut env);
+    shuffled.sort_by_key(|ele| ele.to_string());
+    assert_eq!(shuffled, eval_list_expr("('(1 2 3 4 5))", &mut env));
+
+    for _ in 0..20 {
+        let num = eval_int("(choice ('(1 2 3)))", &mut env);
+        assert!((1..=3).contains(&num));
+    }
+    check_expr_error_in_env(
+        "(choice (regex-find-all \"x\" \"abc\"))",
+        "choice: the list is empty",
+        &mut env,
+    );
+}
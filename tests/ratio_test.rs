@@ -0,0 +1,87 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_expr_error_in_env, check_float_expr, check_integer_expr,
+    check_number_syntax_err, check_string_expr_in_env,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_integer_division_gives_ratio() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(number->string (/ 7 2))", "7/2", &mut env);
+    check_string_expr_in_env("(number->string (/ 6 4))", "3/2", &mut env);
+    check_string_expr_in_env("(number->string (/ (- 0 1) 3))", "-1/3", &mut env);
+    check_string_expr_in_env("(number->string (/ 1 (- 0 3)))", "-1/3", &mut env);
+    check_integer_expr("(/ 10 5)", 2);
+    check_integer_expr("(/ 12 2 3)", 2);
+    check_float_expr("(/ 7 2.0)", 3.5);
+}
+
+#[test]
+fn test_ratio_arithmetic() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(number->string (+ 1/2 1/3))", "5/6", &mut env);
+    check_string_expr_in_env("(number->string (* 2/3 3/4))", "1/2", &mut env);
+    check_string_expr_in_env("(number->string (- 1/2 1))", "-1/2", &mut env);
+    check_integer_expr("(+ 1/2 1/2)", 1);
+    check_integer_expr("(* 7/2 2)", 7);
+    check_float_expr("(+ 1/2 0.25)", 0.75);
+}
+
+#[test]
+fn test_ratio_literals() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(number->string 6/8)", "3/4", &mut env);
+    check_string_expr_in_env(
+        "(number->string (string->number \"-6/8\"))",
+        "-3/4",
+        &mut env,
+    );
+    check_integer_expr("4/2", 2);
+    check_number_syntax_err("1/0", "Invalid number literal `1/0`: denominator is zero");
+    check_number_syntax_err("1/2/3", "Invalid number literal `1/2/3`: more than one `/`");
+    check_number_syntax_err(
+        "1.5/2",
+        "Invalid number literal `1.5/2`: both parts of a ratio must be integers",
+    );
+}
+
+#[test]
+fn test_numerator_denominator() {
+    check_integer_expr("(numerator (/ 6 4))", 3);
+    check_integer_expr("(denominator (/ 6 4))", 2);
+    check_integer_expr("(numerator 5)", 5);
+    check_integer_expr("(denominator 5)", 1);
+
+    let mut env = Env::new();
+    check_expr_error_in_env(
+        "(numerator 1.5)",
+        "numerator: expected an exact number, got: 1.5",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_exactness_conversions() {
+    check_float_expr("(exact->inexact 7/2)", 3.5);
+    check_float_expr("(exact->inexact 3)", 3.0);
+
+    let mut env = Env::new();
+    check_string_expr_in_env("(number->string (inexact->exact 0.5))", "1/2", &mut env);
+    check_integer_expr("(inexact->exact 4.0)", 4);
+}
+
+#[test]
+fn test_ratio_comparisons() {
+    check_conditional("(== 1/2 0.5)", true);
+    check_conditional("(== (/ 2 4) 1/2)", true);
+    check_conditional("(< 1/3 1/2)", true);
+    check_conditional("(> 1/3 0.3)", true);
+    check_conditional("(< 1/2 1)", true);
+    check_conditional("(>= 100000000000000000001/2 50000000000000000000)", true);
+}
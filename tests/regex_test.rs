@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_expr_error_in_env, check_list_expr_eq, check_string_expr_in_env,
+    check_unit_expr_in_env, eval_expr_error_in_env, eval_expr_in_env, eval_list_expr,
+};
+
+use spressolisp::{ast::ExprKind, env::Env};
+
+#[test]
+fn test_regex_match_and_find() {
+    let mut env = Env::new();
+    check_conditional(r#"(regex-match "^\\d+$" "12345")"#, true);
+    check_conditional(r#"(regex-match "^\\d+$" "12a45")"#, false);
+    check_string_expr_in_env(r#"(regex-find "\\d+" "abc 123 456")"#, "123", &mut env);
+    check_unit_expr_in_env(r#"(regex-find "\\d+" "abc")"#, &mut env);
+
+    let res = eval_list_expr(r#"(regex-find-all "\\d+" "abc 123 456")"#, &mut env);
+    check_list_expr_eq(res, r#"('("123" "456"))"#);
+}
+
+#[test]
+fn test_regex_captures() {
+    let mut env = Env::new();
+    let res = eval_list_expr(
+        r#"(regex-captures "(\\w+)=(\\d+)(x)?" "log: level=42")"#,
+        &mut env,
+    );
+    check_list_expr_eq(res, r#"('("level=42" "level" "42" ()))"#);
+    check_unit_expr_in_env(r#"(regex-captures "(\\w+)=(\\d+)" "nope")"#, &mut env);
+}
+
+#[test]
+fn test_regex_replace_and_split() {
+    let mut env = Env::new();
+    check_string_expr_in_env(
+        r#"(regex-replace "(\\w+)=(\\d+)" "a=1 b=2" "$2=$1")"#,
+        "1=a 2=b",
+        &mut env,
+    );
+    let res = eval_list_expr(r#"(regex-split "\\s*,\\s*" "a , b,c")"#, &mut env);
+    check_list_expr_eq(res, r#"('("a" "b" "c"))"#);
+}
+
+#[test]
+fn test_compiled_regex_is_reused() {
+    let mut env = Env::new();
+    let re = eval_expr_in_env(r#"(define re (regex "^ERROR"))"#, &mut env);
+    assert!(matches!(re.kind, ExprKind::Regex(_)));
+
+    let res = eval_list_expr(
+        r#"(map ('("ERROR a" "INFO b" "ERROR c")) (lambda l (if (regex-match re l) 1 0)))"#,
+        &mut env,
+    );
+    check_list_expr_eq(res, "('(1 0 1))");
+}
+
+#[test]
+fn test_invalid_regex() {
+    let mut env = Env::new();
+    let err = eval_expr_error_in_env(r#"(regex "(abc")"#, &mut env);
+    assert!(err.text().starts_with("Invalid regex:"), "{}", err.text());
+    check_expr_error_in_env(
+        "(regex-match 1 \"a\")",
+        "Expected a regex or a pattern string, got: 1 ",
+        &mut env,
+    );
+}
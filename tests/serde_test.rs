@@ -0,0 +1,114 @@
+#![cfg(feature = "serde")]
+
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::eval_expr_in_env;
+
+use std::collections::HashMap;
+
+use spressolisp::{
+    ast::Expr,
+    convert::{FromExpr, IntoExpr},
+    env::Env,
+};
+
+fn to_json(expr: &str) -> String {
+    let mut env = Env::new();
+    let res = eval_expr_in_env(expr, &mut env);
+    serde_json::to_string(&res).unwrap()
+}
+
+fn to_json_error(expr: &str) -> String {
+    let mut env = Env::new();
+    let res = eval_expr_in_env(expr, &mut env);
+    serde_json::to_string(&res).unwrap_err().to_string()
+}
+
+#[test]
+fn test_serialize() {
+    assert_eq!(to_json("(+ 1 2)"), "3");
+    assert_eq!(to_json("2.5"), "2.5");
+    assert_eq!(to_json("(/ 1 4)"), "0.25");
+    assert_eq!(to_json("(* 9223372036854775807 2)"), "18446744073709551614");
+    assert_eq!(to_json("\"hi\""), "\"hi\"");
+    assert_eq!(to_json("('red)"), "\"red\"");
+    assert_eq!(to_json("(< 1 2)"), "true");
+    assert_eq!(to_json("()"), "null");
+    assert_eq!(to_json("('(1 \"a\" (2.5 ())))"), "[1,\"a\",[2.5,null]]");
+}
+
+#[test]
+fn test_serialize_errors() {
+    assert_eq!(
+        to_json_error("(lambda x x)"),
+        "cannot serialize a function, only data like numbers, strings and lists can be serialized"
+    );
+    assert_eq!(
+        to_json_error("+"),
+        "cannot serialize a function, only data like numbers, strings and lists can be serialized"
+    );
+    assert_eq!(
+        to_json_error("(expt 2 200)"),
+        "cannot serialize 1606938044258990275541962092341162602522202993782792835301376, it does not fit in 128 bits"
+    );
+}
+
+#[test]
+fn test_deserialize_into_env() {
+    let json =
+        r#"{"name": "spresso", "sizes": [1, 2.5, 18446744073709551615], "ok": true, "none": null}"#;
+    let data: Expr = serde_json::from_str(json).unwrap();
+
+    // a map becomes an object, a list of (key value) pairs
+    let map = HashMap::<String, Expr>::from_expr(data.clone())
+        .ok()
+        .unwrap();
+    assert_eq!(map.len(), 4);
+    assert_eq!(map["ok"], true.into_expr());
+    assert_eq!(map["none"], ().into_expr());
+
+    let mut env = Env::new();
+    env.insert("sizes", map["sizes"].clone());
+    let res = eval_expr_in_env("(== sizes ('(1 2.5 18446744073709551615)))", &mut env);
+    assert_eq!(res, true.into_expr());
+}
+
+#[test]
+fn test_round_trip() {
+    let mut env = Env::new();
+    let res = eval_expr_in_env("('(1 \"a\" (2.5 ())))", &mut env);
+    let json = serde_json::to_string(&res).unwrap();
+    let back: Expr = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, res);
+}
+
+#[test]
+fn test_map_round_trip() {
+    let json = r#"{"name":"spresso","sizes":[1,2.5],"nested":{"ok":true}}"#;
+    let data: Expr = serde_json::from_str(json).unwrap();
+    assert_eq!(serde_json::to_string(&data).unwrap(), json);
+
+    let mut env = Env::new();
+    env.insert("data", data);
+    let res = eval_expr_in_env(
+        r#"(== data (json-parse "{\"name\": \"spresso\", \"sizes\": [1, 2.5], \"nested\": {\"ok\": true}}"))"#,
+        &mut env,
+    );
+    assert_eq!(res, true.into_expr());
+
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1i64);
+    assert_eq!(
+        serde_json::to_string(&map.into_expr()).unwrap(),
+        r#"{"a":1}"#
+    );
+    assert_eq!(to_json("(json-parse \"{}\")"), "{}");
+    assert_eq!(to_json("('((\"a\" 1)))"), r#"[["a",1]]"#);
+    assert_eq!(
+        to_json_error("('(object (1 2)))"),
+        "serialize: an object can only contain (key value) pairs with string keys"
+    );
+}
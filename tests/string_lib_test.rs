@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_expr_error_in_env, check_float_expr, check_integer_expr,
+    check_list_expr_eq, check_string_expr_in_env, eval_list_expr,
+};
+
+use spressolisp::env::Env;
+
+#[test]
+fn test_length_and_substring() {
+    let mut env = Env::new();
+    check_integer_expr("(string-length \"hello\")", 5);
+    check_integer_expr("(string-length \"héllo wörld\")", 11);
+    check_string_expr_in_env("(substring \"héllo\" 1 3)", "él", &mut env);
+    check_string_expr_in_env("(substring \"héllo\" 2)", "llo", &mut env);
+    check_expr_error_in_env(
+        "(substring \"abc\" 2 5)",
+        "substring: range 2..5 is out of bounds for a string of length 3",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_append_and_repeat() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(string-append \"foo\" \"bar\" \"!\")", "foobar!", &mut env);
+    check_string_expr_in_env("(concat \"a\" \"b\")", "ab", &mut env);
+    check_string_expr_in_env("(repeat \"ab\" 3)", "ababab", &mut env);
+    check_string_expr_in_env("(repeat \"ab\" 0)", "", &mut env);
+    check_expr_error_in_env(
+        "(repeat \"ab\" 9223372036854775807)",
+        "repeat: the result would be longer than 268435456 bytes",
+        &mut env,
+    );
+    check_expr_error_in_env("(concat \"a\" 1)", "Expected a string, got: 1 ", &mut env);
+}
+
+#[test]
+fn test_split_and_join() {
+    let mut env = Env::new();
+    let res = eval_list_expr("(split \"a,b,c\" \",\")", &mut env);
+    check_list_expr_eq(res, "('(\"a\" \"b\" \"c\"))");
+
+    let res = eval_list_expr("(split \"  a  b \")", &mut env);
+    check_list_expr_eq(res, "('(\"a\" \"b\"))");
+
+    let res = eval_list_expr("(split \"ñé\" \"\")", &mut env);
+    check_list_expr_eq(res, "('(\"ñ\" \"é\"))");
+
+    check_string_expr_in_env("(join ('(\"a\" \"b\" \"c\")) \"-\")", "a-b-c", &mut env);
+    check_string_expr_in_env("(join (split \"x y\"))", "xy", &mut env);
+}
+
+#[test]
+fn test_case_and_trim() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(trim \"  hi \\n\")", "hi", &mut env);
+    check_string_expr_in_env("(upcase \"straße\")", "STRASSE", &mut env);
+    check_string_expr_in_env("(downcase \"ÀB\")", "àb", &mut env);
+}
+
+#[test]
+fn test_searching() {
+    let mut env = Env::new();
+    check_conditional("(starts-with? \"hello\" \"he\")", true);
+    check_conditional("(ends-with? \"hello\" \"he\")", false);
+    check_conditional("(contains? \"hello\" \"ell\")", true);
+    check_integer_expr("(index-of \"héllo\" \"l\")", 2);
+    check_integer_expr("(index-of \"hello\" \"z\")", -1);
+    check_string_expr_in_env("(replace \"a-b-c\" \"-\" \"+\")", "a+b+c", &mut env);
+}
+
+#[test]
+fn test_number_conversions() {
+    let mut env = Env::new();
+    check_integer_expr("(string->number \"42\")", 42);
+    check_float_expr("(string->number \" 4.5 \")", 4.5);
+    check_string_expr_in_env("(number->string 42)", "42", &mut env);
+    check_string_expr_in_env("(number->string (/ 5.0 2))", "2.5", &mut env);
+    check_expr_error_in_env(
+        "(string->number \"abc\")",
+        "Could not parse number from string: \"abc\"",
+        &mut env,
+    );
+}
@@ -3,7 +3,9 @@ extern crate assert_float_eq;
 
 pub mod common;
 
-use common::{check_string_expr_in_env, eval_expr_in_env};
+use common::{
+    check_string_expr_in_env, check_syntax_err, eval_expr_error_in_env, eval_expr_in_env,
+};
 
 use spressolisp::env::Env;
 
@@ -20,3 +22,46 @@ fn test_string_space() {
     eval_expr_in_env("(define x \"hello world\")", &mut env);
     check_string_expr_in_env("x", "hello world", &mut env);
 }
+
+#[test]
+fn test_string_escapes() {
+    let mut env = Env::new();
+    check_string_expr_in_env(r#""say \"hi\"""#, "say \"hi\"", &mut env);
+    check_string_expr_in_env(r#""a\\b""#, "a\\b", &mut env);
+    check_string_expr_in_env(r#""line\nnext\ttab""#, "line\nnext\ttab", &mut env);
+    check_string_expr_in_env(r#""\u{48}\u{1F600}""#, "H\u{1F600}", &mut env);
+}
+
+#[test]
+fn test_string_invalid_escapes() {
+    check_syntax_err(r#""\q""#, "Unknown escape sequence in string: \\q");
+    check_syntax_err(
+        r#""\u{110000}""#,
+        "Invalid unicode code point in string: 110000",
+    );
+    check_syntax_err(
+        r#""\u41""#,
+        "Invalid unicode escape sequence in string, expected \\u{...}",
+    );
+}
+
+#[test]
+fn test_string_not_closed() {
+    check_syntax_err("(define x \"hello)", "String not closed");
+    check_syntax_err(r#""hello\""#, "String not closed");
+    check_syntax_err("\"abc\n", "String not closed");
+    check_syntax_err("(print \"abc\n\n", "String not closed");
+}
+
+#[test]
+fn test_multi_line_string() {
+    let mut env = Env::new();
+    eval_expr_in_env("(define x \"hello\nworld\")\n(define y 1)", &mut env);
+    check_string_expr_in_env("x", "hello\nworld", &mut env);
+
+    // both lines of the string should be shown in the error
+    let err = eval_expr_error_in_env("(if \"multi\nline\" 1 2)", &mut env);
+    let shown = err.to_string();
+    assert!(shown.contains("(if \"multi"), "{}", shown);
+    assert!(shown.contains("line\" 1 2)"), "{}", shown);
+}
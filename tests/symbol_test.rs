@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use common::{
+    check_conditional, check_expr_error_in_env, check_list_expr_eq, check_string_expr_in_env,
+    eval_expr_in_env, eval_list_expr,
+};
+
+use spressolisp::{
+    ast::{Atom, ExprKind},
+    env::Env,
+};
+
+#[test]
+fn test_quoted_symbol_is_a_value() {
+    let mut env = Env::new();
+    eval_expr_in_env("(define color ('red))", &mut env);
+    let res = eval_expr_in_env("color", &mut env);
+    assert_eq!(res.kind, ExprKind::Atom(Atom::Symbol("red".to_string())));
+
+    check_conditional("(symbol? (quote red))", true);
+    check_conditional("(symbol? \"red\")", false);
+    check_string_expr_in_env("(symbol->string color)", "red", &mut env);
+
+    let res = eval_list_expr("('(a b c))", &mut env);
+    check_list_expr_eq(res, "(quote (a b c))");
+}
+
+#[test]
+fn test_string_to_symbol() {
+    let mut env = Env::new();
+    let res = eval_expr_in_env("(string->symbol \"hello\")", &mut env);
+    assert_eq!(res.kind, ExprKind::Atom(Atom::Symbol("hello".to_string())));
+    check_expr_error_in_env(
+        "(string->symbol \"a b\")",
+        "Not a valid symbol name: \"a b\"",
+        &mut env,
+    );
+    check_expr_error_in_env("(symbol->string 1)", "Expected a symbol, got: 1 ", &mut env);
+}
+
+#[test]
+fn test_gensym_is_unique() {
+    let mut env = Env::new();
+    let first = eval_expr_in_env("(gensym)", &mut env);
+    let second = eval_expr_in_env("(gensym)", &mut env);
+    assert_ne!(first, second);
+    check_conditional("(symbol? (gensym))", true);
+    check_string_expr_in_env("(symbol->string (gensym \"tmp\"))", "tmp__3", &mut env);
+}
@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+pub mod common;
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::{
+    check_expr_error_in_env, check_integer_expr_in_env, check_string_expr_in_env,
+    check_unit_expr_in_env, eval_as_in_env,
+};
+
+use spressolisp::env::Env;
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as i64
+}
+
+#[test]
+fn test_current_time() {
+    let mut env = Env::new();
+    let before = now_millis();
+    let time = eval_as_in_env::<i64>("(current-time)", &mut env);
+    assert!(before <= time && time <= now_millis());
+    check_expr_error_in_env(
+        "(current-time 1)",
+        "`current-time` needs 0 arguments, got 1",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_sleep_and_elapsed_time() {
+    let mut env = Env::new();
+    let start = eval_as_in_env::<f64>("(monotonic-time)", &mut env);
+    check_unit_expr_in_env("(sleep 20)", &mut env);
+    assert!(eval_as_in_env::<f64>("(monotonic-time)", &mut env) - start >= 20.0);
+
+    let elapsed = eval_as_in_env::<f64>(
+        "(elapsed-time (define a 1) (sleep 15) (define a 2))",
+        &mut env,
+    );
+    assert!(elapsed >= 15.0);
+    check_integer_expr_in_env("a", 2, &mut env);
+
+    check_expr_error_in_env(
+        "(sleep -5)",
+        "sleep: expected a non-negative duration, got: -5",
+        &mut env,
+    );
+    check_expr_error_in_env("(sleep 1e300)", "sleep: the duration is too long", &mut env);
+    check_expr_error_in_env(
+        "(sleep 18446744073709551616000)",
+        "sleep: the duration is too long",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_format_time() {
+    let mut env = Env::new();
+    check_string_expr_in_env("(format-time 0)", "1970-01-01T00:00:00.000Z", &mut env);
+    check_string_expr_in_env(
+        "(format-time 1709296200123)",
+        "2024-03-01T12:30:00.123Z",
+        &mut env,
+    );
+    check_string_expr_in_env(
+        r#"(format-time 1709296200123 "%d/%m/%Y %H:%M")"#,
+        "01/03/2024 12:30",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(format-time 0 "%Q")"#,
+        "format-time: invalid format `%Q`",
+        &mut env,
+    );
+}
+
+#[test]
+fn test_parse_time() {
+    let mut env = Env::new();
+    check_integer_expr_in_env(
+        r#"(parse-time "2024-03-01T12:30:00.123Z")"#,
+        1709296200123,
+        &mut env,
+    );
+    check_integer_expr_in_env(
+        r#"(parse-time "2024-03-01T14:30:00+02:00")"#,
+        1709296200000,
+        &mut env,
+    );
+    check_integer_expr_in_env(
+        r#"(parse-time "2024-03-01T12:30:00")"#,
+        1709296200000,
+        &mut env,
+    );
+    check_integer_expr_in_env(r#"(parse-time "2024-03-01")"#, 1709251200000, &mut env);
+    check_integer_expr_in_env(
+        r#"(parse-time "01/03/2024" "%d/%m/%Y")"#,
+        1709251200000,
+        &mut env,
+    );
+    check_integer_expr_in_env(
+        r#"(parse-time (format-time 1709296200123))"#,
+        1709296200123,
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(parse-time "yesterday")"#,
+        "parse-time: `yesterday` is not a date in ISO-8601",
+        &mut env,
+    );
+    check_expr_error_in_env(
+        r#"(parse-time "2024-03-01" "%d/%m/%Y")"#,
+        "parse-time: `2024-03-01` is not a date in the format `%d/%m/%Y`",
+        &mut env,
+    );
+}